@@ -0,0 +1,699 @@
+//! Instruction builders. [`vault_instruction`] and [`stake_instruction`] take the
+//! account and argument structs generated for every instruction, the instance
+//! builders below fill in the PDAs of the common flows.
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::instruction::Instruction,
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{token, token_2022};
+
+use crate::pda;
+
+/// Builds a vault instruction from `vault::accounts::*` and `vault::instruction::*`
+pub fn vault_instruction(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: vault::ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+/// Builds a stake instruction from `stake::accounts::*` and `stake::instruction::*`
+pub fn stake_instruction(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: stake::ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+/// The collateral side of a deposit or redemption
+#[derive(Debug, Clone, Copy)]
+pub struct Collateral {
+    pub mint: Pubkey,
+    /// Either the Token or the Token-2022 program
+    pub token_program: Pubkey,
+    /// The collateral token account paying a deposit or receiving a redemption
+    pub account: Pubkey,
+    /// The price feed of the asset, only needed in oracle mode
+    pub oracle: Option<Pubkey>,
+}
+
+/// Builds instructions against the vault instance keyed by `salt`
+#[derive(Debug, Clone, Copy)]
+pub struct VaultInstance {
+    pub salt: [u8; 8],
+    pub vault_state: Pubkey,
+    pub vault_token_mint: Pubkey,
+    /// The token program of the vault token, the legacy vault predates Token-2022
+    pub token_program: Pubkey,
+    /// The treasury vault token account, needed by assets that charge a fee
+    pub treasury: Option<Pubkey>,
+}
+
+impl VaultInstance {
+    pub fn new(salt: [u8; 8]) -> Self {
+        let (vault_state, _) = pda::vault::vault_state(&salt);
+        let (vault_token_mint, _) = pda::vault::vault_token_mint(&salt, &vault_state);
+
+        let token_program = if salt == vault::LEGACY_SALT { token::ID } else { token_2022::ID };
+
+        Self {
+            salt,
+            vault_state,
+            vault_token_mint,
+            token_program,
+            treasury: None,
+        }
+    }
+
+    pub fn with_treasury(mut self, treasury: Pubkey) -> Self {
+        self.treasury = Some(treasury);
+        self
+    }
+
+    fn deposit_accounts(
+        &self,
+        minter: Pubkey,
+        collateral: &Collateral,
+        vault_token_account: Pubkey,
+    ) -> vault::accounts::Deposit {
+        vault::accounts::Deposit {
+            system_program: system_program::ID,
+            token_program: self.token_program,
+            collateral_token_program: collateral.token_program,
+            program_collateral: pda::vault::program_collateral(&self.salt, &self.vault_state, &collateral.mint).0,
+            caller_collateral: collateral.account,
+            caller_vault_token: vault_token_account,
+            treasury_vault_token: self.treasury,
+            exchange_rate: pda::vault::exchange_rate(&self.salt, &self.vault_state, &collateral.mint).0,
+            oracle: collateral.oracle,
+            instructions: None,
+            user_permissions: pda::vault::permissions(&self.salt, &self.vault_state, &minter).0,
+            vault_token_mint: self.vault_token_mint,
+            collateral_token_mint: collateral.mint,
+            vault_state: self.vault_state,
+            minter,
+        }
+    }
+
+    fn redeem_accounts(
+        &self,
+        redeemer: Pubkey,
+        collateral: &Collateral,
+        vault_token_account: Pubkey,
+    ) -> vault::accounts::Redeem {
+        vault::accounts::Redeem {
+            system_program: system_program::ID,
+            token_program: self.token_program,
+            collateral_token_program: collateral.token_program,
+            program_collateral: pda::vault::program_collateral(&self.salt, &self.vault_state, &collateral.mint).0,
+            caller_collateral: collateral.account,
+            caller_vault_token: vault_token_account,
+            treasury_vault_token: self.treasury,
+            exchange_rate: pda::vault::exchange_rate(&self.salt, &self.vault_state, &collateral.mint).0,
+            oracle: collateral.oracle,
+            instructions: None,
+            user_permissions: pda::vault::permissions(&self.salt, &self.vault_state, &redeemer).0,
+            vault_token_mint: self.vault_token_mint,
+            vault_state: self.vault_state,
+            redeemer,
+            collateral_token_mint: collateral.mint,
+        }
+    }
+
+    /// Deposits `collat` from the minter's collateral account and mints vault tokens
+    /// to the minter's own `vault_token_account`
+    pub fn deposit(
+        &self,
+        minter: Pubkey,
+        collateral: &Collateral,
+        vault_token_account: Pubkey,
+        collat: u64,
+        min_amount_out: u64,
+        expiry_slot: Option<u64>,
+    ) -> Instruction {
+        vault_instruction(
+            self.deposit_accounts(minter, collateral, vault_token_account),
+            vault::instruction::Deposit { salt: self.salt, collat, min_amount_out, expiry_slot },
+        )
+    }
+
+    /// Like [`Self::deposit`] but mints to a vault token account of any owner
+    pub fn deposit_for(
+        &self,
+        minter: Pubkey,
+        collateral: &Collateral,
+        receiver_vault_token: Pubkey,
+        collat: u64,
+        min_amount_out: u64,
+        expiry_slot: Option<u64>,
+    ) -> Instruction {
+        vault_instruction(
+            self.deposit_accounts(minter, collateral, receiver_vault_token),
+            vault::instruction::DepositFor { salt: self.salt, collat, min_amount_out, expiry_slot },
+        )
+    }
+
+    /// Burns `amt` vault tokens of the redeemer and pays the collateral to the
+    /// redeemer's own collateral account
+    pub fn redeem(
+        &self,
+        redeemer: Pubkey,
+        collateral: &Collateral,
+        vault_token_account: Pubkey,
+        amt: u64,
+        min_amount_out: u64,
+        expiry_slot: Option<u64>,
+    ) -> Instruction {
+        vault_instruction(
+            self.redeem_accounts(redeemer, collateral, vault_token_account),
+            vault::instruction::Redeem { salt: self.salt, amt, min_amount_out, expiry_slot },
+        )
+    }
+
+    /// Like [`Self::redeem`] but pays the collateral to an account of any owner
+    pub fn redeem_to(
+        &self,
+        redeemer: Pubkey,
+        collateral: &Collateral,
+        vault_token_account: Pubkey,
+        amt: u64,
+        min_amount_out: u64,
+        expiry_slot: Option<u64>,
+    ) -> Instruction {
+        vault_instruction(
+            self.redeem_accounts(redeemer, collateral, vault_token_account),
+            vault::instruction::RedeemTo { salt: self.salt, amt, min_amount_out, expiry_slot },
+        )
+    }
+
+    /// Escrows `amt` vault tokens into redeem ticket `ticket_id`, which has to be
+    /// the `next_ticket` of the asset's exchange rate
+    #[allow(clippy::too_many_arguments)]
+    pub fn request_redeem(
+        &self,
+        redeemer: Pubkey,
+        collateral: &Collateral,
+        vault_token_account: Pubkey,
+        ticket_id: u64,
+        amt: u64,
+        min_amount_out: u64,
+        expiry_slot: Option<u64>,
+    ) -> Instruction {
+        vault_instruction(
+            vault::accounts::RequestRedeem {
+                system_program: system_program::ID,
+                token_program: self.token_program,
+                caller_vault_token: vault_token_account,
+                treasury_vault_token: self.treasury,
+                redeem_escrow: pda::vault::redeem_escrow(&self.vault_state).0,
+                exchange_rate: pda::vault::exchange_rate(&self.salt, &self.vault_state, &collateral.mint).0,
+                ticket: pda::vault::redeem_ticket(&self.vault_state, &collateral.mint, ticket_id).0,
+                oracle: collateral.oracle,
+                user_permissions: pda::vault::permissions(&self.salt, &self.vault_state, &redeemer).0,
+                vault_token_mint: self.vault_token_mint,
+                collateral_token_mint: collateral.mint,
+                vault_state: self.vault_state,
+                redeemer,
+            },
+            vault::instruction::RequestRedeem { salt: self.salt, amt, min_amount_out, expiry_slot },
+        )
+    }
+
+    /// Pays out fulfilled ticket `ticket_id` to the owner's collateral account
+    pub fn claim_redeem(&self, owner: Pubkey, collateral: &Collateral, ticket_id: u64) -> Instruction {
+        vault_instruction(
+            vault::accounts::ClaimRedeem {
+                collateral_token_program: collateral.token_program,
+                ticket: pda::vault::redeem_ticket(&self.vault_state, &collateral.mint, ticket_id).0,
+                exchange_rate: pda::vault::exchange_rate(&self.salt, &self.vault_state, &collateral.mint).0,
+                redeem_reserve: pda::vault::redeem_reserve(&self.vault_state, &collateral.mint).0,
+                owner_collateral: collateral.account,
+                collateral_token_mint: collateral.mint,
+                vault_state: self.vault_state,
+                owner,
+            },
+            vault::instruction::ClaimRedeem { salt: self.salt },
+        )
+    }
+
+    fn user_permissions_accounts(&self, caller: Pubkey, user: &Pubkey) -> vault::accounts::UserPermissions {
+        vault::accounts::UserPermissions {
+            vault_state: self.vault_state,
+            user_permissions: pda::vault::permissions(&self.salt, &self.vault_state, user).0,
+            caller_role: pda::vault::role(&self.vault_state, &caller).0,
+            caller,
+            system_program: system_program::ID,
+        }
+    }
+
+    pub fn whitelist_minter(&self, caller: Pubkey, user: Pubkey) -> Instruction {
+        vault_instruction(
+            self.user_permissions_accounts(caller, &user),
+            vault::instruction::WhitelistMinter { salt: self.salt, user },
+        )
+    }
+
+    pub fn remove_minter(&self, caller: Pubkey, user: Pubkey) -> Instruction {
+        vault_instruction(
+            self.user_permissions_accounts(caller, &user),
+            vault::instruction::RemoveMinter { salt: self.salt, user },
+        )
+    }
+
+    pub fn whitelist_redeemer(&self, caller: Pubkey, user: Pubkey) -> Instruction {
+        vault_instruction(
+            self.user_permissions_accounts(caller, &user),
+            vault::instruction::WhitelistRedeemer { salt: self.salt, user },
+        )
+    }
+
+    pub fn remove_redeemer(&self, caller: Pubkey, user: Pubkey) -> Instruction {
+        vault_instruction(
+            self.user_permissions_accounts(caller, &user),
+            vault::instruction::RemoveRedeemer { salt: self.salt, user },
+        )
+    }
+
+    fn freeze_holder_accounts(
+        &self,
+        caller: Pubkey,
+        caller_role: Option<Pubkey>,
+        holder_vault_token: Pubkey,
+    ) -> vault::accounts::FreezeHolder {
+        vault::accounts::FreezeHolder {
+            token_program: self.token_program,
+            vault_state: self.vault_state,
+            vault_token_mint: self.vault_token_mint,
+            holder_vault_token,
+            caller_role,
+            caller,
+        }
+    }
+
+    /// Freezes a vault token account. A guardian passes its [`pda::vault::role`],
+    /// the admin passes `None`.
+    pub fn freeze_holder(
+        &self,
+        caller: Pubkey,
+        caller_role: Option<Pubkey>,
+        holder_vault_token: Pubkey,
+    ) -> Instruction {
+        vault_instruction(
+            self.freeze_holder_accounts(caller, caller_role, holder_vault_token),
+            vault::instruction::FreezeHolder { salt: self.salt },
+        )
+    }
+
+    /// Thaws a frozen vault token account, as the admin
+    pub fn thaw_holder(&self, caller: Pubkey, holder_vault_token: Pubkey) -> Instruction {
+        vault_instruction(
+            self.freeze_holder_accounts(caller, None, holder_vault_token),
+            vault::instruction::ThawHolder { salt: self.salt },
+        )
+    }
+}
+
+/// Builds instructions against the staking vault instance keyed by `salt`
+#[derive(Debug, Clone, Copy)]
+pub struct StakeInstance {
+    pub salt: [u8; 8],
+    pub vault_state: Pubkey,
+    pub deposit_token: Pubkey,
+    /// The program of the deposit token, either Token or Token-2022
+    pub token_program: Pubkey,
+    pub staking_token: Pubkey,
+    pub vault_token_account: Pubkey,
+}
+
+impl StakeInstance {
+    pub fn new(salt: [u8; 8], deposit_token: Pubkey, token_program: Pubkey) -> Self {
+        let (vault_state, _) = pda::stake::vault_state(&salt);
+
+        Self {
+            salt,
+            vault_state,
+            deposit_token,
+            token_program,
+            staking_token: pda::stake::staking_token(&vault_state).0,
+            vault_token_account: pda::stake::vault_token_account(&vault_state).0,
+        }
+    }
+
+    /// Stakes `amt` deposit tokens and mints staking tokens to the user
+    pub fn stake(
+        &self,
+        user: Pubkey,
+        user_deposit_token_account: Pubkey,
+        user_staking_token_account: Pubkey,
+        amt: u64,
+    ) -> Instruction {
+        stake_instruction(
+            stake::accounts::Stake {
+                vault_state: self.vault_state,
+                deposit_token: self.deposit_token,
+                staking_token: self.staking_token,
+                user_deposit_token_account,
+                user_staking_token_account,
+                vault_token_account: self.vault_token_account,
+                blacklisted: pda::stake::blacklisted(&self.salt, &user).0,
+                user,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            stake::instruction::Stake { salt: self.salt, amt },
+        )
+    }
+
+    fn unstake_accounts(
+        &self,
+        user: Pubkey,
+        user_deposit_token_account: Pubkey,
+        user_staking_token_account: Pubkey,
+    ) -> stake::accounts::Unstake {
+        stake::accounts::Unstake {
+            token_program: self.token_program,
+            vault_state: self.vault_state,
+            deposit_token: self.deposit_token,
+            staking_token: self.staking_token,
+            user_staking_token_account,
+            user_deposit_token_account,
+            vault_token_account: self.vault_token_account,
+            blacklisted: pda::stake::blacklisted(&self.salt, &user).0,
+            user_data: pda::stake::user_data(&self.vault_state, &user).0,
+            user,
+            system_program: system_program::ID,
+        }
+    }
+
+    /// Burns `shares` and queues their assets behind the cooldown
+    pub fn start_unstake(
+        &self,
+        user: Pubkey,
+        user_deposit_token_account: Pubkey,
+        user_staking_token_account: Pubkey,
+        shares: u64,
+    ) -> Instruction {
+        stake_instruction(
+            self.unstake_accounts(user, user_deposit_token_account, user_staking_token_account),
+            stake::instruction::StartUnstake { salt: self.salt, shares },
+        )
+    }
+
+    /// Withdraws `assets` whose cooldown has passed
+    pub fn unstake(
+        &self,
+        user: Pubkey,
+        user_deposit_token_account: Pubkey,
+        user_staking_token_account: Pubkey,
+        assets: u64,
+    ) -> Instruction {
+        stake_instruction(
+            self.unstake_accounts(user, user_deposit_token_account, user_staking_token_account),
+            stake::instruction::Unstake { salt: self.salt, assets },
+        )
+    }
+
+    /// Transfers `amt` deposit tokens from a rewarder into the vault to be vested
+    pub fn reward(&self, caller: Pubkey, caller_token_account: Pubkey, amt: u64) -> Instruction {
+        stake_instruction(
+            stake::accounts::Reward {
+                vault_state: self.vault_state,
+                deposit_token: self.deposit_token,
+                caller_token_account,
+                vault_token_account: self.vault_token_account,
+                caller,
+                token_program: self.token_program,
+            },
+            stake::instruction::Reward { amt, salt: self.salt },
+        )
+    }
+
+    fn blacklist_accounts(&self, caller: Pubkey, user: &Pubkey) -> stake::accounts::Blacklist {
+        stake::accounts::Blacklist {
+            vault_state: self.vault_state,
+            blacklisted: pda::stake::blacklisted(&self.salt, user).0,
+            caller,
+            system_program: system_program::ID,
+        }
+    }
+
+    pub fn blacklist(&self, caller: Pubkey, user: Pubkey) -> Instruction {
+        stake_instruction(
+            self.blacklist_accounts(caller, &user),
+            stake::instruction::Blacklist { salt: self.salt, user },
+        )
+    }
+
+    pub fn remove_from_blacklist(&self, caller: Pubkey, user: Pubkey) -> Instruction {
+        stake_instruction(
+            self.blacklist_accounts(caller, &user),
+            stake::instruction::RemoveFromBlacklist { salt: self.salt, user },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{AnchorDeserialize, Discriminator};
+
+    use super::*;
+
+    /// Checks the instruction targets `program_id`, carries `accounts` in order and
+    /// that its data decodes back into `args`
+    fn assert_round_trip<T>(ix: &Instruction, program_id: Pubkey, accounts: impl ToAccountMetas, args: &T)
+    where
+        T: InstructionData + AnchorDeserialize + Discriminator,
+    {
+        assert_eq!(ix.program_id, program_id);
+        assert_eq!(ix.accounts, accounts.to_account_metas(None));
+        assert_eq!(&ix.data[..8], T::DISCRIMINATOR.as_ref());
+        let decoded = T::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(decoded.data(), args.data());
+    }
+
+    /// Derives a PDA from literal seeds, independent of the helpers in [`pda`]
+    fn find(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(seeds, program_id).0
+    }
+
+    fn collateral() -> Collateral {
+        Collateral {
+            mint: Pubkey::new_unique(),
+            token_program: token_2022::ID,
+            account: Pubkey::new_unique(),
+            oracle: None,
+        }
+    }
+
+    #[test]
+    fn vault_instructions_round_trip() {
+        let salt = [7; 8];
+        let treasury = Pubkey::new_unique();
+        let vault = VaultInstance::new(salt).with_treasury(treasury);
+        let user = Pubkey::new_unique();
+        let vault_token_account = Pubkey::new_unique();
+        let collateral = collateral();
+        let asset = collateral.mint;
+
+        let id = vault::ID;
+        let state = find(&[b"vault-state", &salt], &id);
+        let mint = find(&[b"mint", state.as_ref()], &id);
+        let exchange_rate = find(&[b"exchange-rate", state.as_ref(), asset.as_ref()], &id);
+        let program_collateral = find(&[b"token-account", state.as_ref(), asset.as_ref()], &id);
+        let permissions = find(&[b"vault-state", state.as_ref(), user.as_ref()], &id);
+
+        let args = vault::instruction::Deposit { salt, collat: 10, min_amount_out: 9, expiry_slot: Some(5) };
+        assert_round_trip(
+            &vault.deposit(user, &collateral, vault_token_account, 10, 9, Some(5)),
+            id,
+            vault::accounts::Deposit {
+                system_program: system_program::ID,
+                token_program: token_2022::ID,
+                collateral_token_program: token_2022::ID,
+                program_collateral,
+                caller_collateral: collateral.account,
+                caller_vault_token: vault_token_account,
+                treasury_vault_token: Some(treasury),
+                exchange_rate,
+                oracle: None,
+                instructions: None,
+                user_permissions: permissions,
+                vault_token_mint: mint,
+                collateral_token_mint: asset,
+                vault_state: state,
+                minter: user,
+            },
+            &args,
+        );
+
+        let args = vault::instruction::RedeemTo { salt, amt: 3, min_amount_out: 0, expiry_slot: None };
+        assert_round_trip(
+            &vault.redeem_to(user, &collateral, vault_token_account, 3, 0, None),
+            id,
+            vault::accounts::Redeem {
+                system_program: system_program::ID,
+                token_program: token_2022::ID,
+                collateral_token_program: token_2022::ID,
+                program_collateral,
+                caller_collateral: collateral.account,
+                caller_vault_token: vault_token_account,
+                treasury_vault_token: Some(treasury),
+                exchange_rate,
+                oracle: None,
+                instructions: None,
+                user_permissions: permissions,
+                vault_token_mint: mint,
+                vault_state: state,
+                redeemer: user,
+                collateral_token_mint: asset,
+            },
+            &args,
+        );
+
+        let args = vault::instruction::RequestRedeem { salt, amt: 4, min_amount_out: 2, expiry_slot: Some(8) };
+        assert_round_trip(
+            &vault.request_redeem(user, &collateral, vault_token_account, 6, 4, 2, Some(8)),
+            id,
+            vault::accounts::RequestRedeem {
+                system_program: system_program::ID,
+                token_program: token_2022::ID,
+                caller_vault_token: vault_token_account,
+                treasury_vault_token: Some(treasury),
+                redeem_escrow: find(&[b"redeem-escrow", state.as_ref()], &id),
+                exchange_rate,
+                ticket: find(&[b"redeem-ticket", state.as_ref(), asset.as_ref(), &6u64.to_le_bytes()], &id),
+                oracle: None,
+                user_permissions: permissions,
+                vault_token_mint: mint,
+                collateral_token_mint: asset,
+                vault_state: state,
+                redeemer: user,
+            },
+            &args,
+        );
+
+        let caller = Pubkey::new_unique();
+        let args = vault::instruction::WhitelistMinter { salt, user };
+        assert_round_trip(
+            &vault.whitelist_minter(caller, user),
+            id,
+            vault::accounts::UserPermissions {
+                vault_state: state,
+                user_permissions: permissions,
+                caller_role: find(&[b"role", state.as_ref(), caller.as_ref()], &id),
+                caller,
+                system_program: system_program::ID,
+            },
+            &args,
+        );
+    }
+
+    #[test]
+    fn freeze_holder_passes_the_caller_role() {
+        let salt = [7; 8];
+        let vault = VaultInstance::new(salt);
+        let guardian = Pubkey::new_unique();
+        let role = pda::vault::role(&vault.vault_state, &guardian).0;
+        let holder = Pubkey::new_unique();
+
+        let state = find(&[b"vault-state", &salt], &vault::ID);
+        let args = vault::instruction::FreezeHolder { salt };
+        assert_round_trip(
+            &vault.freeze_holder(guardian, Some(role), holder),
+            vault::ID,
+            vault::accounts::FreezeHolder {
+                token_program: token_2022::ID,
+                vault_state: state,
+                vault_token_mint: find(&[b"mint", state.as_ref()], &vault::ID),
+                holder_vault_token: holder,
+                caller_role: Some(role),
+                caller: guardian,
+            },
+            &args,
+        );
+
+        // The admin needs no role, the optional account is filled with the program id
+        let ix = vault.thaw_holder(guardian, holder);
+        assert!(ix.accounts.iter().all(|meta| meta.pubkey != role));
+        assert!(ix.accounts.iter().any(|meta| meta.pubkey == vault::ID));
+    }
+
+    #[test]
+    fn legacy_vault_uses_the_token_program() {
+        let vault = VaultInstance::new(vault::LEGACY_SALT);
+        assert_eq!(vault.token_program, token::ID);
+        assert_eq!(vault.vault_token_mint, find(&[b"mint"], &vault::ID));
+    }
+
+    #[test]
+    fn stake_instructions_round_trip() {
+        let salt = [3; 8];
+        let deposit_token = Pubkey::new_unique();
+        let stake = StakeInstance::new(salt, deposit_token, token_2022::ID);
+        let user = Pubkey::new_unique();
+        let deposit_account = Pubkey::new_unique();
+        let staking_account = Pubkey::new_unique();
+
+        let id = stake::ID;
+        let state = find(&[b"vault-state", &salt], &id);
+        let staking_token = find(&[b"staking-token", state.as_ref()], &id);
+        let vault_token_account = find(&[b"vault-token-account", state.as_ref()], &id);
+        let blacklisted = find(&[b"vault-state", &salt, user.as_ref()], &id);
+
+        let args = stake::instruction::Stake { salt, amt: 11 };
+        assert_round_trip(
+            &stake.stake(user, deposit_account, staking_account, 11),
+            id,
+            stake::accounts::Stake {
+                vault_state: state,
+                deposit_token,
+                staking_token,
+                user_deposit_token_account: deposit_account,
+                user_staking_token_account: staking_account,
+                vault_token_account,
+                blacklisted,
+                user,
+                token_program: token_2022::ID,
+                system_program: system_program::ID,
+            },
+            &args,
+        );
+
+        let args = stake::instruction::Unstake { salt, assets: 4 };
+        assert_round_trip(
+            &stake.unstake(user, deposit_account, staking_account, 4),
+            id,
+            stake::accounts::Unstake {
+                token_program: token_2022::ID,
+                vault_state: state,
+                deposit_token,
+                staking_token,
+                user_staking_token_account: staking_account,
+                user_deposit_token_account: deposit_account,
+                vault_token_account,
+                blacklisted,
+                user_data: find(&[b"user-data", user.as_ref(), state.as_ref()], &id),
+                user,
+                system_program: system_program::ID,
+            },
+            &args,
+        );
+
+        let args = stake::instruction::Blacklist { salt, user };
+        assert_round_trip(
+            &stake.blacklist(user, user),
+            id,
+            stake::accounts::Blacklist {
+                vault_state: state,
+                blacklisted,
+                caller: user,
+                system_program: system_program::ID,
+            },
+            &args,
+        );
+    }
+}
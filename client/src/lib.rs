@@ -0,0 +1,9 @@
+//! Off-chain helpers for the vault and stake programs: PDA derivation,
+//! instruction builders and account deserializers.
+
+pub mod instruction;
+pub mod pda;
+pub mod state;
+
+pub use stake;
+pub use vault;
@@ -0,0 +1,231 @@
+//! PDA derivation for every seed of the vault and stake programs. Each helper
+//! returns the address along with its bump.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::metadata::mpl_token_metadata;
+
+/// The Metaplex metadata account of `mint`
+pub fn metadata(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"metadata", mpl_token_metadata::ID.as_ref(), mint.as_ref()],
+        &mpl_token_metadata::ID,
+    )
+}
+
+pub mod vault {
+    use super::*;
+    use ::vault::{
+        ASSET_REGISTRY_SEED, EXCHANGE_RATE_SEED, ID, MINT_SEED, REDEEM_ESCROW_SEED,
+        REDEEM_RESERVE_SEED, REDEEM_TICKET_SEED, ROLE_SEED, TIMELOCK_SEED, TOKEN_ACCOUNT_SEED,
+        VAULT_STATE_SEED, WITHDRAW_POLICY_SEED, WITHDRAW_PROPOSAL_SEED, vault_scope, VaultScope,
+    };
+
+    /// The state of the vault instance keyed by `salt`, every other PDA lives under it
+    pub fn vault_state(salt: &[u8; 8]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[VAULT_STATE_SEED, salt.scope()], &ID)
+    }
+
+    pub fn vault_token_mint(salt: &[u8; 8], vault_state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[MINT_SEED, vault_scope(salt, vault_state)], &ID)
+    }
+
+    pub fn asset_registry(vault_state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ASSET_REGISTRY_SEED, vault_state.as_ref()], &ID)
+    }
+
+    pub fn exchange_rate(salt: &[u8; 8], vault_state: &Pubkey, asset: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[EXCHANGE_RATE_SEED, vault_scope(salt, vault_state), asset.as_ref()], &ID)
+    }
+
+    /// The program owned collateral account of `asset`
+    pub fn program_collateral(salt: &[u8; 8], vault_state: &Pubkey, asset: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[TOKEN_ACCOUNT_SEED, vault_scope(salt, vault_state), asset.as_ref()], &ID)
+    }
+
+    pub fn permissions(salt: &[u8; 8], vault_state: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[VAULT_STATE_SEED, vault_scope(salt, vault_state), user.as_ref()], &ID)
+    }
+
+    pub fn role(vault_state: &Pubkey, holder: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ROLE_SEED, vault_state.as_ref(), holder.as_ref()], &ID)
+    }
+
+    /// The timelock operation queued as number `id`
+    pub fn timelock_operation(vault_state: &Pubkey, id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[TIMELOCK_SEED, vault_state.as_ref(), &id.to_le_bytes()], &ID)
+    }
+
+    pub fn withdraw_proposal(vault_state: &Pubkey, id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[WITHDRAW_PROPOSAL_SEED, vault_state.as_ref(), &id.to_le_bytes()],
+            &ID,
+        )
+    }
+
+    pub fn withdraw_policy(vault_state: &Pubkey, destination: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[WITHDRAW_POLICY_SEED, vault_state.as_ref(), destination.as_ref()],
+            &ID,
+        )
+    }
+
+    pub fn redeem_ticket(vault_state: &Pubkey, asset: &Pubkey, id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[REDEEM_TICKET_SEED, vault_state.as_ref(), asset.as_ref(), &id.to_le_bytes()],
+            &ID,
+        )
+    }
+
+    /// Holds the vault tokens of open redeem tickets
+    pub fn redeem_escrow(vault_state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[REDEEM_ESCROW_SEED, vault_state.as_ref()], &ID)
+    }
+
+    /// Holds the collateral of fulfilled redeem tickets of `asset`
+    pub fn redeem_reserve(vault_state: &Pubkey, asset: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[REDEEM_RESERVE_SEED, vault_state.as_ref(), asset.as_ref()], &ID)
+    }
+}
+
+pub mod stake {
+    use super::*;
+    use ::stake::{ID, STAKING_TOKEN_SEED, USER_DATA_SEED, VAULT_STATE_SEED, VAULT_TOKEN_ACCOUNT_SEED};
+
+    /// The state of the staking vault instance keyed by `salt`
+    pub fn vault_state(salt: &[u8; 8]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[VAULT_STATE_SEED, salt], &ID)
+    }
+
+    pub fn staking_token(vault_state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[STAKING_TOKEN_SEED, vault_state.as_ref()], &ID)
+    }
+
+    /// The staking vault's account for the deposit token
+    pub fn vault_token_account(vault_state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[VAULT_TOKEN_ACCOUNT_SEED, vault_state.as_ref()], &ID)
+    }
+
+    pub fn user_data(vault_state: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[USER_DATA_SEED, user.as_ref(), vault_state.as_ref()], &ID)
+    }
+
+    pub fn blacklisted(salt: &[u8; 8], user: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[VAULT_STATE_SEED, salt, user.as_ref()], &ID)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(seeds, program_id)
+    }
+
+    #[test]
+    fn vault_pdas_match_the_program_seeds() {
+        let id = ::vault::ID;
+        let salt = [1, 2, 3, 4, 5, 6, 7, 8];
+        let asset = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+
+        let state = vault::vault_state(&salt);
+        assert_eq!(state, find(&[b"vault-state", &salt], &id));
+        let state = state.0;
+
+        assert_eq!(vault::vault_token_mint(&salt, &state), find(&[b"mint", state.as_ref()], &id));
+        assert_eq!(vault::asset_registry(&state), find(&[b"asset-registry", state.as_ref()], &id));
+        assert_eq!(
+            vault::exchange_rate(&salt, &state, &asset),
+            find(&[b"exchange-rate", state.as_ref(), asset.as_ref()], &id)
+        );
+        assert_eq!(
+            vault::program_collateral(&salt, &state, &asset),
+            find(&[b"token-account", state.as_ref(), asset.as_ref()], &id)
+        );
+        assert_eq!(
+            vault::permissions(&salt, &state, &user),
+            find(&[b"vault-state", state.as_ref(), user.as_ref()], &id)
+        );
+        assert_eq!(vault::role(&state, &user), find(&[b"role", state.as_ref(), user.as_ref()], &id));
+        assert_eq!(
+            vault::timelock_operation(&state, 3),
+            find(&[b"timelock", state.as_ref(), &3u64.to_le_bytes()], &id)
+        );
+        assert_eq!(
+            vault::withdraw_proposal(&state, 4),
+            find(&[b"withdraw-proposal", state.as_ref(), &4u64.to_le_bytes()], &id)
+        );
+        assert_eq!(
+            vault::withdraw_policy(&state, &user),
+            find(&[b"withdraw-policy", state.as_ref(), user.as_ref()], &id)
+        );
+        assert_eq!(
+            vault::redeem_ticket(&state, &asset, 5),
+            find(&[b"redeem-ticket", state.as_ref(), asset.as_ref(), &5u64.to_le_bytes()], &id)
+        );
+        assert_eq!(vault::redeem_escrow(&state), find(&[b"redeem-escrow", state.as_ref()], &id));
+        assert_eq!(
+            vault::redeem_reserve(&state, &asset),
+            find(&[b"redeem-reserve", state.as_ref(), asset.as_ref()], &id)
+        );
+    }
+
+    #[test]
+    fn legacy_vault_pdas_keep_the_unscoped_seeds() {
+        let id = ::vault::ID;
+        let salt = ::vault::LEGACY_SALT;
+        let asset = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+
+        let state = vault::vault_state(&salt);
+        assert_eq!(state, find(&[b"vault-state"], &id));
+        let state = state.0;
+
+        assert_eq!(vault::vault_token_mint(&salt, &state), find(&[b"mint"], &id));
+        assert_eq!(
+            vault::program_collateral(&salt, &state, &asset),
+            find(&[b"token-account", asset.as_ref()], &id)
+        );
+        assert_eq!(vault::exchange_rate(&salt, &state, &asset), find(&[b"exchange-rate", asset.as_ref()], &id));
+        assert_eq!(vault::permissions(&salt, &state, &user), find(&[b"vault-state", user.as_ref()], &id));
+        // Accounts the legacy vault never had live under its vault state like any other
+        assert_eq!(vault::asset_registry(&state), find(&[b"asset-registry", state.as_ref()], &id));
+    }
+
+    #[test]
+    fn stake_pdas_match_the_program_seeds() {
+        let id = ::stake::ID;
+        let salt = [8, 7, 6, 5, 4, 3, 2, 1];
+        let user = Pubkey::new_unique();
+
+        let state = stake::vault_state(&salt);
+        assert_eq!(state, find(&[b"vault-state", &salt], &id));
+        let state = state.0;
+
+        assert_eq!(stake::staking_token(&state), find(&[b"staking-token", state.as_ref()], &id));
+        assert_eq!(
+            stake::vault_token_account(&state),
+            find(&[b"vault-token-account", state.as_ref()], &id)
+        );
+        // The user comes before the vault state in the user data seeds
+        assert_eq!(
+            stake::user_data(&state, &user),
+            find(&[b"user-data", user.as_ref(), state.as_ref()], &id)
+        );
+        assert_ne!(stake::user_data(&state, &user), find(&[b"user-data", state.as_ref(), user.as_ref()], &id));
+        assert_eq!(
+            stake::blacklisted(&salt, &user),
+            find(&[b"vault-state", &salt, user.as_ref()], &id)
+        );
+    }
+
+    #[test]
+    fn metadata_matches_metaplex_seeds() {
+        let mint = Pubkey::new_unique();
+        assert_eq!(
+            metadata(&mint),
+            find(&[b"metadata", mpl_token_metadata::ID.as_ref(), mint.as_ref()], &mpl_token_metadata::ID)
+        );
+    }
+}
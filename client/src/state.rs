@@ -0,0 +1,42 @@
+//! Account deserializers, each checks the account discriminator before decoding.
+
+use anchor_lang::{AccountDeserialize, Result};
+
+/// Decodes any account of the vault or stake programs from its raw data
+pub fn deserialize<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+pub mod vault {
+    use super::*;
+    use ::vault::{ExchangeRate, Permissions, VaultState};
+
+    pub fn vault_state(data: &[u8]) -> Result<VaultState> {
+        deserialize(data)
+    }
+
+    pub fn exchange_rate(data: &[u8]) -> Result<ExchangeRate> {
+        deserialize(data)
+    }
+
+    pub fn permissions(data: &[u8]) -> Result<Permissions> {
+        deserialize(data)
+    }
+}
+
+pub mod stake {
+    use super::*;
+    use ::stake::{Blacklisted, UserPDA, VaultState};
+
+    pub fn vault_state(data: &[u8]) -> Result<VaultState> {
+        deserialize(data)
+    }
+
+    pub fn user_data(data: &[u8]) -> Result<UserPDA> {
+        deserialize(data)
+    }
+
+    pub fn blacklisted(data: &[u8]) -> Result<Blacklisted> {
+        deserialize(data)
+    }
+}
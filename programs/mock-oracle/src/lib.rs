@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+declare_id!("DwGvRKv5eWdXspZTKbpYFkKGAmLwZKn2amWAXFF9KDkf");
+
+const PRICE_FEED_SEED: &[u8] = b"price-feed";
+
+/// A local stand-in for a price oracle, used to exercise the vault's oracle mode in tests.
+///
+/// Layout after the discriminator: price (i64) at offset 8, publish time (i64) at offset 16.
+#[account]
+pub struct PriceFeed {
+    pub price: i64,
+    pub publish_time: i64,
+    pub authority: Pubkey,
+}
+
+#[program]
+pub mod mock_oracle {
+    use super::*;
+
+    pub fn set_price(ctx: Context<SetPrice>, _feed_id: [u8; 8], price: i64, publish_time: i64) -> Result<()> {
+        let feed = &mut ctx.accounts.price_feed;
+
+        if feed.authority == Pubkey::default() {
+            feed.authority = ctx.accounts.authority.key();
+        } else if feed.authority != ctx.accounts.authority.key() {
+            return Err(OracleError::NotAuthority.into());
+        }
+
+        feed.price = price;
+        feed.publish_time = publish_time;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(feed_id: [u8; 8])]
+pub struct SetPrice<'info> {
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 8 + 8 + 32,
+        seeds = [PRICE_FEED_SEED, feed_id.as_ref()],
+        bump
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[error_code]
+pub enum OracleError {
+    #[msg("The caller is not the price feed authority")]
+    NotAuthority,
+}
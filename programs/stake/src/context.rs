@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{metadata::Metadata, token::{Burn, Mint, MintTo, Token, TokenAccount, Transfer}};
+use anchor_spl::{metadata::Metadata, token_interface::{Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked}};
 use super::*;
 
 #[derive(Accounts)]
@@ -9,14 +9,14 @@ pub struct InitializeVaultState<'info> {
     #[account(
         init, 
         payer = caller, 
-        space = 8 + (2 * 32) + (3 * 8) + (3 * 4) + 1 + (32 * 20), 
+        space = 8 + (3 * 32) + (3 * 8) + (3 * 4) + 1 + (32 * 20), 
         seeds = [VAULT_STATE_SEED, salt.as_ref()], 
         bump
     )]
     pub vault_state: Box<Account<'info, VaultState>>,
 
     #[account(mut)]
-    pub deposit_token: Box<Account<'info, Mint>>,
+    pub deposit_token: Box<InterfaceAccount<'info, Mint>>,
     #[account(mut)]
     pub caller: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -41,7 +41,7 @@ pub struct InitializeProgramAccounts<'info> {
         mint::authority = vault_state,
         bump
     )]
-    pub staking_token: Box<Account<'info, Mint>>,
+    pub staking_token: Box<InterfaceAccount<'info, Mint>>,
 
     /// The deposit token account for this vault and admin
     #[account(
@@ -52,7 +52,7 @@ pub struct InitializeProgramAccounts<'info> {
         token::authority = vault_state,
         bump
     )]
-    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    pub vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     /// CHECK: New Metaplex Account creation
     #[account(
         mut,
@@ -62,51 +62,80 @@ pub struct InitializeProgramAccounts<'info> {
     )]
     pub metadata: UncheckedAccount<'info>,
     #[account(mut)]
-    pub deposit_token: Box<Account<'info, Mint>>,
+    pub deposit_token: Box<InterfaceAccount<'info, Mint>>,
     #[account(mut)]
     pub caller: Signer<'info>,
     pub rent: Sysvar<'info, Rent>,
-    pub token_program: Program<'info, Token>,
+    /// The program of the deposit token, either Token or Token-2022. The staking token
+    /// is created under the same program.
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub token_metadata_program: Program<'info, Metadata>,
 }
 
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct UpdateMetadata<'info> {
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.as_ref()],
+        bump
+    )]
+    pub vault_state: Box<Account<'info, VaultState>>,
+    #[account(
+        seeds = [STAKING_TOKEN_SEED, vault_state.key().as_ref()],
+        bump
+    )]
+    pub staking_token: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: The Metaplex metadata of the staking token
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), staking_token.key().as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub metadata: UncheckedAccount<'info>,
+    pub caller: Signer<'info>,
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
 #[derive(Accounts)]
 #[instruction(salt: [u8; 8], amt: u64)]
 pub struct Stake<'info> {
     #[account(
         mut,
         seeds = [VAULT_STATE_SEED, salt.as_ref()], 
-        bump
+        bump,
+        has_one = deposit_token @ StakeError::BadDepositToken
     )]
     pub vault_state: Account<'info, VaultState>,
+    pub deposit_token: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
         seeds = [STAKING_TOKEN_SEED, vault_state.key().as_ref()],
         bump
     )]
-    pub staking_token: Account<'info, Mint>,
+    pub staking_token: InterfaceAccount<'info, Mint>,
     /// The user deposit token account, we're going to transfer from this
     #[account(
         mut,
         token::mint = vault_state.deposit_token,
         token::authority = user,
     )]
-    pub user_deposit_token_account: Account<'info, TokenAccount>,
+    pub user_deposit_token_account: InterfaceAccount<'info, TokenAccount>,
     /// The users staking token account, we're going to mint to this
     #[account(
         mut,
         token::mint = staking_token,
         token::authority = user,
     )]
-    pub user_staking_token_account: Account<'info, TokenAccount>,
+    pub user_staking_token_account: InterfaceAccount<'info, TokenAccount>,
     /// The vault's account for the deposit token
     #[account(
         mut,
         seeds = [VAULT_TOKEN_ACCOUNT_SEED, vault_state.key().as_ref()],
         bump
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(
         init_if_needed, 
         payer = user,
@@ -117,7 +146,7 @@ pub struct Stake<'info> {
     pub blacklisted: Account<'info, Blacklisted>,
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
@@ -126,15 +155,16 @@ impl<'info> Stake<'info> {
         &self,
         amount: u64,
     ) -> Result<()> {
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: self.user_deposit_token_account.to_account_info(),
+            mint: self.deposit_token.to_account_info(),
             to: self.vault_token_account.to_account_info(),
             authority: self.user.to_account_info(),
         };
 
         let cpi_program = self.token_program.to_account_info();
         let cpi = CpiContext::new(cpi_program, cpi_accounts);
-        token::transfer(cpi, amount)
+        token_interface::transfer_checked(cpi, amount, self.deposit_token.decimals)
     }
 
     pub fn mint_tokens_to_user(
@@ -157,7 +187,7 @@ impl<'info> Stake<'info> {
             seeds,
         );
 
-        token::mint_to(cpi_ctx, amount)
+        token_interface::mint_to(cpi_ctx, amount)
     }
 
     pub fn check_min_shares(&self) -> Result<()> {
@@ -173,28 +203,31 @@ impl<'info> Stake<'info> {
 #[derive(Accounts)]
 #[instruction(salt: [u8; 8])]
 pub struct Unstake<'info> {
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 
     #[account(
         mut,
         seeds = [VAULT_STATE_SEED, salt.as_ref()], 
-        bump
+        bump,
+        has_one = deposit_token @ StakeError::BadDepositToken
     )]
     pub vault_state: Account<'info, VaultState>,
 
+    pub deposit_token: InterfaceAccount<'info, Mint>,
+
     #[account(
         mut,
         seeds = [STAKING_TOKEN_SEED, vault_state.key().as_ref()],
         bump
     )]
-    pub staking_token: Account<'info, Mint>,
+    pub staking_token: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         token::mint = staking_token,
         token::authority = user,
     )]
-    pub user_staking_token_account: Account<'info, TokenAccount>,
+    pub user_staking_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// The user deposit account were going to send collateral too
     #[account(
@@ -202,7 +235,7 @@ pub struct Unstake<'info> {
         token::mint = vault_state.deposit_token,
         token::authority = user,
     )]
-    pub user_deposit_token_account: Account<'info, TokenAccount>,
+    pub user_deposit_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// The vault's token account for the deposit token
     #[account(
@@ -210,7 +243,7 @@ pub struct Unstake<'info> {
         seeds = [VAULT_TOKEN_ACCOUNT_SEED, vault_state.key().as_ref()],
         bump
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         init_if_needed, 
@@ -241,8 +274,9 @@ impl<'info> Unstake<'info> {
         salt: &[u8; 8],
         amount: u64,
     ) -> Result<()> {
-         let accounts = Transfer {
+         let accounts = TransferChecked {
             from: self.vault_token_account.to_account_info(),
+            mint: self.deposit_token.to_account_info(),
             to: self.user_deposit_token_account.to_account_info(),
             authority: self.vault_state.to_account_info(),
         };
@@ -255,7 +289,7 @@ impl<'info> Unstake<'info> {
             seeds,
         );
 
-        token::transfer(cpi_ctx, amount)
+        token_interface::transfer_checked(cpi_ctx, amount, self.deposit_token.decimals)
     }
 
     pub fn burn_tokens_from_user(
@@ -273,7 +307,7 @@ impl<'info> Unstake<'info> {
             burn_instruction,
         );
 
-        token::burn(cpi_ctx, amount)
+        token_interface::burn(cpi_ctx, amount)
     }
 
     pub fn check_min_shares(&self) -> Result<()> {
@@ -292,17 +326,20 @@ pub struct Reward<'info> {
     #[account(
         mut,
         seeds = [VAULT_STATE_SEED, salt.as_ref()], 
-        bump
+        bump,
+        has_one = deposit_token @ StakeError::BadDepositToken
     )]
     pub vault_state: Account<'info, VaultState>,
 
+    pub deposit_token: InterfaceAccount<'info, Mint>,
+
     /// The callers deposit token account
     #[account(
         mut,
         token::mint = vault_state.deposit_token,
         token::authority = caller,
     )]
-    pub caller_token_account: Account<'info, TokenAccount>,
+    pub caller_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// The vault's token account for the deposit token
     #[account(
@@ -310,11 +347,11 @@ pub struct Reward<'info> {
         seeds = [VAULT_TOKEN_ACCOUNT_SEED, vault_state.key().as_ref()],
         bump
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut)]
     pub caller: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
@@ -391,6 +428,19 @@ pub struct TransferAdmin<'info> {
     pub caller: Signer<'info>,
 }
 
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct AdminHandover<'info> {
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, salt.as_ref()], 
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+}
+
 #[derive(Accounts)]
 #[instruction(salt: [u8; 8])]
 pub struct CheckAssets<'info> {
@@ -2,11 +2,13 @@ use std::collections::VecDeque;
 
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    token::{self, Transfer},
+    token_interface::{self, TransferChecked},
     metadata::{
         create_metadata_accounts_v3,
         mpl_token_metadata::types::DataV2,
         CreateMetadataAccountsV3,
+        update_metadata_accounts_v2,
+        UpdateMetadataAccountsV2,
     }, 
 };
 mod context;
@@ -14,10 +16,13 @@ use context::*;
 
 declare_id!("36axVA5TApdCi8u7LV1ReekkEDJNGKMK2sL8akfi5e4Z");
 
-const STAKING_TOKEN_SEED: &[u8] = b"staking-token";
-const VAULT_TOKEN_ACCOUNT_SEED: &[u8] = b"vault-token-account";
-const USER_DATA_SEED: &[u8] = b"user-data";
-const VAULT_STATE_SEED: &[u8] = b"vault-state";
+pub const STAKING_TOKEN_SEED: &[u8] = b"staking-token";
+pub const VAULT_TOKEN_ACCOUNT_SEED: &[u8] = b"vault-token-account";
+pub const USER_DATA_SEED: &[u8] = b"user-data";
+pub const VAULT_STATE_SEED: &[u8] = b"vault-state";
+/// Vaults created before pending_admin was added were allocated 8 + 2 * 32 + 3 * 8 + 3 * 4
+/// + 1 + 20 * 32 bytes, the state with this many rewarders still fits in them
+const MAX_REWARDERS: usize = 18;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
 pub struct MetadataParams {
@@ -26,6 +31,20 @@ pub struct MetadataParams {
     pub uri: String,
 }
 
+impl MetadataParams {
+    pub fn into_data(self) -> DataV2 {
+        DataV2 {
+            name: self.name,
+            symbol: self.symbol,
+            uri: self.uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        }
+    }
+}
+
 #[account]
 pub struct UserPDA {
     pub assets_available: u64,
@@ -50,6 +69,8 @@ pub struct VaultState {
     pub vesting_period: u32,
     pub bump: u8,
     pub rewarders: Vec<Pubkey>,
+    /// The proposed admin, takes over once it accepts
+    pub pending_admin: Pubkey,
 }
 
 #[program]
@@ -74,9 +95,9 @@ pub mod stake {
         ctx.accounts.vault_state.vesting_period = 8 * 3600;
 
         emit!(NewVaultEvent{
-            admin: admin,
+            admin,
             token: ctx.accounts.deposit_token.key(),
-            salt: salt,
+            salt,
         });
 
         Ok(())
@@ -94,34 +115,26 @@ pub mod stake {
         let seeds: &[&[u8]] = &[VAULT_STATE_SEED, &salt, &[ctx.accounts.vault_state.bump]];
         let signer = &[seeds][..];
 
-        let token_data = DataV2 {
-            name: metadata.name,
-            symbol: metadata.symbol,
-            uri: metadata.uri,
-            seller_fee_basis_points: 0,
-            creators: None,
-            collection: None,
-            uses: None,
-        };
-
         let metadata_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_metadata_program.to_account_info(),
             CreateMetadataAccountsV3 {
                 payer: ctx.accounts.caller.to_account_info(),
-                update_authority: ctx.accounts.caller.to_account_info(),
+                update_authority: ctx.accounts.vault_state.to_account_info(),
                 mint: ctx.accounts.staking_token.to_account_info(),
                 metadata: ctx.accounts.metadata.to_account_info(),
                 mint_authority: ctx.accounts.vault_state.to_account_info(),
                 system_program: ctx.accounts.system_program.to_account_info(),
                 rent: ctx.accounts.rent.to_account_info(),
             },
-            &signer
+            signer
         );
 
+        // The vault state holds the update authority so the metadata can be changed
+        // through update_metadata
         create_metadata_accounts_v3(
             metadata_ctx,
-            token_data,
-            false,
+            metadata.into_data(),
+            true,
             true,
             None,
         )?;
@@ -129,6 +142,34 @@ pub mod stake {
         Ok(())
     }
 
+    /// Changes the name, symbol and URI of the staking token
+    pub fn update_metadata(ctx: Context<UpdateMetadata>, salt: [u8; 8], metadata: MetadataParams) -> Result<()> {
+        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
+            return Err(StakeError::NotAdmin.into());
+        }
+
+        emit!(MetadataUpdatedEvent {
+            name: metadata.name.clone(),
+            symbol: metadata.symbol.clone(),
+            uri: metadata.uri.clone(),
+            updated_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        let seeds: &[&[u8]] = &[VAULT_STATE_SEED, &salt, &[ctx.accounts.vault_state.bump]];
+        let signer = &[seeds][..];
+        let metadata_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                update_authority: ctx.accounts.vault_state.to_account_info(),
+            },
+            signer,
+        );
+
+        update_metadata_accounts_v2(metadata_ctx, None, Some(metadata.into_data()), None, None)
+    }
+
     pub fn set_cooldown(ctx: Context<SetCooldown>, salt: [u8; 8], duration: u32) -> Result<()> {
         if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
             return Err(StakeError::NotAdmin.into());
@@ -138,8 +179,8 @@ pub mod stake {
 
         emit!(SetCooldownEvent{
             who: ctx.accounts.caller.key(),
-            duration: duration,
-            salt: salt,
+            duration,
+            salt,
         });
 
         Ok(())
@@ -158,8 +199,8 @@ pub mod stake {
 
         emit!(SetVestingPeriodEvent{
             who: ctx.accounts.caller.key(),
-            duration: duration,
-            salt: salt,
+            duration,
+            salt,
         });
 
         Ok(())
@@ -184,7 +225,7 @@ pub mod stake {
         emit!(AddToBlacklistEvent {
             who: user,
             added_by: ctx.accounts.caller.key(),
-            salt: salt,
+            salt,
         });
 
         Ok(())
@@ -209,7 +250,7 @@ pub mod stake {
         emit!(RemoveFromBlacklistEvent {
             who: user,
             added_by: ctx.accounts.caller.key(),
-            salt: salt,
+            salt,
         });
 
         Ok(())
@@ -222,6 +263,10 @@ pub mod stake {
 
         let rewarders = &mut ctx.accounts.vault_state.rewarders;
 
+        if rewarders.len() >= MAX_REWARDERS {
+            return Err(StakeError::TooManyRewarders.into());
+        }
+
         if !rewarders.contains(&rewarder) {
             rewarders.push(rewarder);
         } else {
@@ -231,7 +276,7 @@ pub mod stake {
         emit!(AddRewarderEvent {
             who: rewarder,
             added_by: ctx.accounts.caller.key(),
-            salt: salt,
+            salt,
         });
 
         Ok(())
@@ -253,7 +298,7 @@ pub mod stake {
         emit!(RemoveRewarderEvent{
             who: rewarder,
             removed_by: ctx.accounts.caller.key(),
-            salt: salt,
+            salt,
         });
 
         Ok(())
@@ -278,8 +323,8 @@ pub mod stake {
         emit!(StakeEvent {
             who: ctx.accounts.user.key(),
             assets: amt,
-            shares: shares,
-            salt: salt,
+            shares,
+            salt,
         });
 
         Ok(())
@@ -307,7 +352,7 @@ pub mod stake {
             who: ctx.accounts.user.key(),
             shares,
             assets,
-            salt: salt,
+            salt,
         });
 
         Ok(())
@@ -331,7 +376,7 @@ pub mod stake {
         emit!(UnstakeEvent {
             who: ctx.accounts.user.key(),
             assets,
-            salt: salt,
+            salt,
         });
 
         Ok(())
@@ -352,8 +397,9 @@ pub mod stake {
         }
 
         // Transfer unstaked tokens to vault
-        let transfer_instruction = Transfer {
+        let transfer_instruction = TransferChecked {
             from: ctx.accounts.caller_token_account.to_account_info(),
+            mint: ctx.accounts.deposit_token.to_account_info(),
             to: ctx.accounts.vault_token_account.to_account_info(),
             authority: ctx.accounts.caller.to_account_info(),
         };
@@ -362,7 +408,7 @@ pub mod stake {
             ctx.accounts.token_program.to_account_info(),
             transfer_instruction,
         );
-        token::transfer(cpi_ctx, amt)?;
+        token_interface::transfer_checked(cpi_ctx, amt, ctx.accounts.deposit_token.decimals)?;
 
         ctx.accounts.vault_state.last_distribution_time = time;
         ctx.accounts.vault_state.total_assets += amt;
@@ -370,15 +416,15 @@ pub mod stake {
 
         emit!(RewardEvent{
             who: ctx.accounts.caller.key(),
-            amt: amt,
-            salt: salt,
+            amt,
+            salt,
         });
 
         Ok(())
     }
 
     pub fn check_available_assets(ctx: Context<CheckAssets>, _salt: [u8; 8])-> Result<u64> {
-        Ok(ctx.accounts.user_data.get_available_assets()?)
+        ctx.accounts.user_data.get_available_assets()
     }
 
     pub fn refresh_cooldowns(ctx: Context<RefreshCooldowns>, _salt: [u8; 8]) -> Result<()> {
@@ -393,18 +439,64 @@ pub mod stake {
         Ok(())
     }
 
-    pub fn transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey, salt: [u8; 8]) -> Result<()> {
+    pub fn propose_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey, salt: [u8; 8]) -> Result<()> {
         if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
             return Err(StakeError::NotAdmin.into());
         }
 
+        ctx.accounts.vault_state.pending_admin = new_admin;
+
+        emit!(AdminProposedEvent {
+            admin: ctx.accounts.caller.key(),
+            pending_admin: new_admin,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AdminHandover>, salt: [u8; 8]) -> Result<()> {
+        let vault_state = &mut ctx.accounts.vault_state;
+
+        if vault_state.pending_admin == Pubkey::default() {
+            return Err(StakeError::NoPendingAdmin.into());
+        }
+
+        if ctx.accounts.caller.key() != vault_state.pending_admin {
+            return Err(StakeError::NotPendingAdmin.into());
+        }
+
+        let old_admin = vault_state.admin;
+        vault_state.admin = vault_state.pending_admin;
+        vault_state.pending_admin = Pubkey::default();
+
+        emit!(AdminTransferEvent {
+            old_admin,
+            new_admin: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_admin_transfer(ctx: Context<AdminHandover>, salt: [u8; 8]) -> Result<()> {
         let vault_state = &mut ctx.accounts.vault_state;
-        vault_state.admin = new_admin;
 
-        emit!(AdminTransferEvent{
-            old_admin: ctx.accounts.caller.key(),
-            new_admin: new_admin,
-            salt: salt,
+        if ctx.accounts.caller.key() != vault_state.admin {
+            return Err(StakeError::NotAdmin.into());
+        }
+
+        if vault_state.pending_admin == Pubkey::default() {
+            return Err(StakeError::NoPendingAdmin.into());
+        }
+
+        let cancelled = vault_state.pending_admin;
+        vault_state.pending_admin = Pubkey::default();
+
+        emit!(AdminTransferCancelledEvent {
+            admin: ctx.accounts.caller.key(),
+            cancelled_admin: cancelled,
+            salt,
         });
 
         Ok(())
@@ -448,11 +540,18 @@ impl VaultState {
         Ok(amt)
     }
 
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(&mut self) {
         self.rewarders = Vec::with_capacity(20);
     }
 }
 
+impl Default for UserPDA {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UserPDA {
     pub fn new() -> Self {
         Self {
@@ -465,7 +564,7 @@ impl UserPDA {
         let time = Clock::get()?.unix_timestamp as u32;
 
         // Iterate over unstake_queue and add cooled-down assets to user data
-        while let Some((cd, assets)) = self.unstake_queue.get(0) {
+        while let Some((cd, assets)) = self.unstake_queue.front() {
             if time >= *cd {
                 self.assets_available += assets;
                 self.unstake_queue.pop_front();
@@ -478,6 +577,15 @@ impl UserPDA {
     }
 }
 
+#[event]
+pub struct MetadataUpdatedEvent {
+    name: String,
+    symbol: String,
+    uri: String,
+    updated_by: Pubkey,
+    salt: [u8; 8],
+}
+
 #[event]
 pub struct SetVestingPeriodEvent {
     who: Pubkey,
@@ -550,6 +658,13 @@ pub struct RemoveFromBlacklistEvent {
     salt: [u8; 8],
 }
 
+#[event]
+pub struct AdminProposedEvent {
+    admin: Pubkey,
+    pending_admin: Pubkey,
+    salt: [u8; 8],
+}
+
 #[event]
 pub struct AdminTransferEvent {
     old_admin: Pubkey,
@@ -557,6 +672,13 @@ pub struct AdminTransferEvent {
     salt: [u8; 8],
 }
 
+#[event]
+pub struct AdminTransferCancelledEvent {
+    admin: Pubkey,
+    cancelled_admin: Pubkey,
+    salt: [u8; 8],
+}
+
 #[event]
 pub struct NewVaultEvent {
     admin: Pubkey,
@@ -598,4 +720,10 @@ pub enum StakeError {
     RewardVestingOngoing,
     #[msg("Vesting period must be non-zero")]
     InvalidVestingPeriod,
+    #[msg("There is no pending admin")]
+    NoPendingAdmin,
+    #[msg("The caller is not the pending admin")]
+    NotPendingAdmin,
+    #[msg("The vault already has the maximum number of rewarders")]
+    TooManyRewarders,
 }
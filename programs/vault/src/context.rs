@@ -1,34 +1,47 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use super::*;
 
 #[derive(Accounts)]
-#[instruction(admin: Pubkey)]
+#[instruction(salt: [u8; 8], admin: Pubkey)]
 pub struct InitializeVaultState<'info> {
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    /// Token-2022, the vault token is created with the permanent delegate extension
+    pub token_program: Interface<'info, TokenInterface>,
     pub token_metadata_program: Program<'info, Metadata>,
 
-    // todo: space
     #[account(
         init, 
         payer = signer, 
-        space = 8 + 8 + (2 + 20 + 20 + 50) * 32,
-        seeds = [VAULT_STATE_SEED],
+        space = VaultState::SPACE,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
         bump
     )]
     pub vault_state: Account<'info, VaultState>,
 
+    #[account(
+        init,
+        payer = signer,
+        space = AssetRegistry::space(0),
+        seeds = [ASSET_REGISTRY_SEED, vault_state.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, AssetRegistry>,
+
     #[account(
         init, 
         payer = signer, 
-        mint::decimals = 9, 
+        mint::decimals = VAULT_TOKEN_DECIMALS, 
         mint::authority = vault_state,
-        seeds = [MINT_SEED], 
+        mint::freeze_authority = vault_state,
+        mint::token_program = token_program,
+        extensions::permanent_delegate::delegate = vault_state,
+        seeds = [MINT_SEED, vault_state.key().as_ref()], 
         bump
     )]
-    pub vault_token: Account<'info, Mint>,
+    pub vault_token: InterfaceAccount<'info, Mint>,
 
     /// CHECK: New Metaplex Account creation
     #[account(
@@ -44,184 +57,450 @@ pub struct InitializeVaultState<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[derive(Accounts)]
+pub struct MigrateVaultState<'info> {
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: The legacy vault state, read and rewritten by the handler
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [VAULT_STATE_SEED],
+        bump
+    )]
+    pub vault_state: UncheckedAccount<'info>,
+
+    /// The legacy vault had no registry, creating it makes the migration one-off
+    #[account(
+        init,
+        payer = admin,
+        space = AssetRegistry::space(0),
+        seeds = [ASSET_REGISTRY_SEED, vault_state.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, AssetRegistry>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+/// Only for the migrated legacy vault, whose state stays at the unscoped seeds
 #[derive(Accounts)]
 #[instruction(asset: Pubkey)]
+pub struct MigrateExchangeRate<'info> {
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        seeds = [VAULT_STATE_SEED],
+        bump = vault_state.bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: The legacy exchange rate of `asset`, grown to the current layout by the handler
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [EXCHANGE_RATE_SEED, asset.as_ref()],
+        bump
+    )]
+    pub exchange_rate: UncheckedAccount<'info>,
+
+    /// Grows by one entry, the legacy vault had no registry to list the asset in
+    #[account(
+        mut,
+        seeds = [ASSET_REGISTRY_SEED, vault_state.key().as_ref()],
+        bump = registry.bump,
+        realloc = AssetRegistry::space(registry.assets.len() + 1),
+        realloc::payer = admin,
+        realloc::zero = false,
+    )]
+    pub registry: Account<'info, AssetRegistry>,
+
+    #[account(address = asset)]
+    pub collateral_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+/// Only for the migrated legacy vault, whose state stays at the unscoped seeds
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct MigratePermissions<'info> {
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        seeds = [VAULT_STATE_SEED],
+        bump = vault_state.bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: The legacy permissions of `user`, grown to the current layout by the handler
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [VAULT_STATE_SEED, user.as_ref()],
+        bump
+    )]
+    pub user_permissions: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], asset: Pubkey)]
 pub struct UpdateAsset<'info> {
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 
     #[account(
         init_if_needed, 
         payer = authority, 
-        seeds = [EXCHANGE_RATE_SEED, asset.as_ref()],
-        space = 8 + 8 + 8 + 32,
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), asset.as_ref()],
+        space = ExchangeRate::SPACE,
         bump
     )]
     pub exchange_rate: Account<'info, ExchangeRate>,
 
+    /// Grows by one entry when a new asset is listed
+    #[account(
+        mut,
+        seeds = [ASSET_REGISTRY_SEED, vault_state.key().as_ref()],
+        bump = registry.bump,
+        realloc = AssetRegistry::space(registry.assets.len() + usize::from(!registry.contains(&asset))),
+        realloc::payer = authority,
+        realloc::zero = false,
+    )]
+    pub registry: Account<'info, AssetRegistry>,
+
     /// The program owned collateral
     #[account(
         init_if_needed,
-        seeds = [TOKEN_ACCOUNT_SEED, asset.as_ref()],
+        seeds = [TOKEN_ACCOUNT_SEED, vault_state.scope(), asset.as_ref()],
         bump,
         payer = authority,
         token::mint = collateral_token_mint,
         token::authority = vault_state,
+        token::token_program = token_program,
     )]
-    pub program_collateral: Account<'info, TokenAccount>,
+    pub program_collateral: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)]
-    pub collateral_token_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub collateral_token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        seeds = [VAULT_STATE_SEED],
+        seeds = [VAULT_STATE_SEED, salt.scope()],
         bump
     )]
     pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    /// CHECK: Only receives the rent of the executed operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
     #[account(mut)]
     pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-#[instruction(collat: u64)]
-pub struct Deposit<'info> {
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-
-    /// The program owned collateral
+#[instruction(salt: [u8; 8])]
+pub struct Pause<'info> {
     #[account(
         mut,
-        seeds = [TOKEN_ACCOUNT_SEED, collateral_token_mint.key().as_ref()],
-        bump,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
     )]
-    pub program_collateral: Account<'info, TokenAccount>,
-    /// The caller owned collateral
+    pub vault_state: Account<'info, VaultState>,
+    /// The caller's roles, only needed when the caller is not the admin
     #[account(
-        mut,
-        token::mint = collateral_token_mint,
-        token::authority = minter,
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), caller.key().as_ref()],
+        bump = caller_role.bump,
     )]
-    pub caller_collateral: Account<'info, TokenAccount>,
-    /// The caller owned vault token account
+    pub caller_role: Option<Account<'info, Role>>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], asset: Pubkey)]
+pub struct PauseAsset<'info> {
     #[account(
         mut,
-        token::mint = vault_token_mint,
-        token::authority = minter,
-    )]
-    pub caller_vault_token: Account<'info, TokenAccount>,
-    #[account(
-        seeds = [EXCHANGE_RATE_SEED, collateral_token_mint.key().as_ref()],
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), asset.as_ref()],
         bump,
     )]
     pub exchange_rate: Account<'info, ExchangeRate>,
     #[account(
-        mut,
-        seeds = [VAULT_STATE_SEED, minter.key().as_ref()],
+        seeds = [VAULT_STATE_SEED, salt.scope()],
         bump
     )]
-    pub user_permissions: Account<'info, Permissions>,
+    pub vault_state: Account<'info, VaultState>,
+    /// The caller's roles, only needed when the caller is not the admin
+    #[account(
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), caller.key().as_ref()],
+        bump = caller_role.bump,
+    )]
+    pub caller_role: Option<Account<'info, Role>>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct QueueOperation<'info> {
     #[account(
         mut,
-        constraint = vault_token_mint.key() == vault_state.vault_token_mint,
-        seeds = [MINT_SEED],
+        seeds = [VAULT_STATE_SEED, salt.scope()],
         bump
     )]
-    pub vault_token_mint: Account<'info, Mint>,
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        init,
+        payer = caller,
+        // The largest action is SetWithdrawPolicy
+        space = 8 + 8 + (1 + 32 + (4 + MAX_POLICY_ASSETS * 32) + 8 + 8) + 8 + 32 + 1,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), vault_state.operation_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
 
-    /// The collateral token mint address,
-    /// we dont need any contraints here becauase we also need an exchange rate address
-    /// that is owned by this program and associated with this mint
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct CancelOperation<'info> {
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    /// CHECK: Only receives the rent of the executed operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    /// The caller's roles, only needed when the caller is not the admin
+    #[account(
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), caller.key().as_ref()],
+        bump = caller_role.bump,
+    )]
+    pub caller_role: Option<Account<'info, Role>>,
     #[account(mut)]
-    pub collateral_token_mint: Account<'info, Mint>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct SetTimelockDelay<'info> {
     #[account(
         mut,
-        seeds = [VAULT_STATE_SEED],
+        seeds = [VAULT_STATE_SEED, salt.scope()],
         bump
     )]
     pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    /// CHECK: Only receives the rent of the executed operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
     #[account(mut)]
-    pub minter: Signer<'info>,
+    pub caller: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct Redeem<'info> {
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-
-    /// The program owned collateral
+#[instruction(salt: [u8; 8], asset: Pubkey)]
+pub struct SetAssetStatus<'info> {
     #[account(
         mut,
-        seeds = [TOKEN_ACCOUNT_SEED, collateral_token_mint.key().as_ref()],
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), asset.as_ref()],
         bump,
     )]
-    pub program_collateral: Account<'info, TokenAccount>,
-    /// The caller owned collateral
+    pub exchange_rate: Account<'info, ExchangeRate>,
     #[account(
         mut,
-        token::mint = collateral_token_mint,
-        token::authority = redeemer,
+        seeds = [ASSET_REGISTRY_SEED, vault_state.key().as_ref()],
+        bump = registry.bump,
     )]
-    pub caller_collateral: Account<'info, TokenAccount>,
-    /// The caller owned vault token account
+    pub registry: Account<'info, AssetRegistry>,
     #[account(
-        mut,
-        token::mint = vault_token_mint,
-        token::authority = redeemer,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
     )]
-    pub caller_vault_token: Account<'info, TokenAccount>,
+    pub vault_state: Account<'info, VaultState>,
+    pub caller: Signer<'info>,
+}
+
+impl<'info> SetAssetStatus<'info> {
+    /// Updates the status in both the registry and the exchange rate
+    pub fn set_status(
+        &mut self,
+        asset: Pubkey,
+        status: AssetStatus,
+    ) -> Result<()> {
+        if self.exchange_rate.status == AssetStatus::Retired {
+            return Err(MintError::AssetRetired.into());
+        }
+
+        let entry = self
+            .registry
+            .assets
+            .iter_mut()
+            .find(|entry| entry.mint == asset)
+            .ok_or(MintError::AssetNotSupported)?;
+        entry.status = status;
+        self.exchange_rate.status = status;
+
+        emit!(AssetStatusUpdatedEvent {
+            asset,
+            status,
+            updated_by: self.caller.key(),
+            salt: self.vault_state.salt,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], asset: Pubkey)]
+pub struct RetireAsset<'info> {
+    /// The token program of the collateral, either Token or Token-2022
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
     #[account(
-        seeds = [EXCHANGE_RATE_SEED, collateral_token_mint.key().as_ref()],
+        mut,
+        close = caller,
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), asset.as_ref()],
         bump,
     )]
     pub exchange_rate: Account<'info, ExchangeRate>,
+    /// The program owned collateral, closed along with the exchange rate
     #[account(
         mut,
-        seeds = [VAULT_STATE_SEED, redeemer.key().as_ref()],
-        bump
+        seeds = [TOKEN_ACCOUNT_SEED, vault_state.scope(), asset.as_ref()],
+        bump,
+        token::token_program = collateral_token_program,
     )]
-    pub user_permissions: Account<'info, Permissions>,
+    pub program_collateral: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: The redeem reserve of the asset, only created once a ticket is fulfilled so it
+    /// may not exist. When it does it has to be empty and is closed as well
     #[account(
         mut,
-        constraint = vault_token_mint.key() == vault_state.vault_token_mint,
-        seeds = [MINT_SEED],
+        seeds = [REDEEM_RESERVE_SEED, vault_state.key().as_ref(), asset.as_ref()],
         bump,
     )]
-    pub vault_token_mint: Account<'info, Mint>,
-
+    pub redeem_reserve: UncheckedAccount<'info>,
     #[account(
         mut,
-        seeds = [VAULT_STATE_SEED],
-        bump,
+        seeds = [ASSET_REGISTRY_SEED, vault_state.key().as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, AssetRegistry>,
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
     )]
     pub vault_state: Account<'info, VaultState>,
     #[account(mut)]
-    pub redeemer: Signer<'info>,
-    /// The collateral token mint address,
-    /// we dont need any contraints here becauase we also need an exchange rate address
-    /// that is owned by this program and associated with this mint
-    #[account(mut)]
-    pub collateral_token_mint: Account<'info, Mint>,
+    pub caller: Signer<'info>,
+}
+
+impl<'info> RetireAsset<'info> {
+    /// The balance of the redeem reserve, None if it was never created
+    pub fn redeem_reserve_amount(&self) -> Result<Option<u64>> {
+        let info = self.redeem_reserve.to_account_info();
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+
+        if info.owner != &self.collateral_token_program.key() {
+            return Err(MintError::InvalidRedeemReserve.into());
+        }
+
+        let reserve = TokenAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(Some(reserve.amount))
+    }
+
+    /// Closes the program collateral account, sending its rent to the caller
+    pub fn close_program_collateral(&self) -> Result<()> {
+        self.close_token_account(self.program_collateral.to_account_info())
+    }
+
+    /// Closes the redeem reserve, sending its rent to the caller
+    pub fn close_redeem_reserve(&self) -> Result<()> {
+        self.close_token_account(self.redeem_reserve.to_account_info())
+    }
+
+    fn close_token_account(&self, account: AccountInfo<'info>) -> Result<()> {
+        let cpi_accounts = CloseAccount {
+            account,
+            destination: self.caller.to_account_info(),
+            authority: self.vault_state.to_account_info(),
+        };
+
+        let seeds: &[&[u8]] = &self.vault_state.signer_seeds();
+        let seeds = &[seeds][..];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.collateral_token_program.to_account_info(),
+            cpi_accounts,
+            seeds,
+        );
+        token_interface::close_account(cpi_ctx)
+    }
 }
 
 #[derive(Accounts)]
-pub struct Withdraw<'info> {
-    pub token_program: Program<'info, Token>,
+#[instruction(salt: [u8; 8])]
+pub struct UpdateMetadata<'info> {
+    pub token_metadata_program: Program<'info, Metadata>,
 
     #[account(
-        mut,
-        seeds = [TOKEN_ACCOUNT_SEED, collat_mint.key().as_ref()],
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        seeds = [MINT_SEED, vault_state.scope()],
         bump
     )]
-    pub program_collat: Account<'info, TokenAccount>,
+    pub vault_token: InterfaceAccount<'info, Mint>,
+    /// CHECK: The Metaplex metadata of the vault token
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), vault_token.key().as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub metadata: UncheckedAccount<'info>,
+    pub caller: Signer<'info>,
+}
 
-    #[account(mut)]
-    pub destination: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub collat_mint: Account<'info, Mint>,
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct SetLimits<'info> {
     #[account(
         mut,
-        seeds = [VAULT_STATE_SEED],
+        seeds = [VAULT_STATE_SEED, salt.scope()],
         bump
     )]
     pub vault_state: Account<'info, VaultState>,
@@ -230,56 +509,1543 @@ pub struct Withdraw<'info> {
 }
 
 #[derive(Accounts)]
-#[instruction(user: Pubkey)]
-pub struct UserPermissions<'info> {
+#[instruction(salt: [u8; 8], asset: Pubkey)]
+pub struct SetAssetLimits<'info> {
     #[account(
         mut,
-        seeds = [VAULT_STATE_SEED],
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), asset.as_ref()],
+        bump,
+    )]
+    pub exchange_rate: Account<'info, ExchangeRate>,
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
         bump
     )]
     pub vault_state: Account<'info, VaultState>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], asset: Pubkey)]
+pub struct SetOracle<'info> {
     #[account(
-        init_if_needed, 
-        space = 8 + 32 + 1 + 1,
-        payer = caller,
-        seeds = [VAULT_STATE_SEED, user.as_ref()],
+        mut,
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), asset.as_ref()],
+        bump,
+    )]
+    pub exchange_rate: Account<'info, ExchangeRate>,
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
         bump
     )]
-    pub user_permissions: Account<'info, Permissions>,
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    /// CHECK: Only receives the rent of the executed operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
     #[account(mut)]
     pub caller: Signer<'info>,
-    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawAddresses<'info> {
+#[instruction(salt: [u8; 8], asset: Pubkey)]
+pub struct SetRateLimits<'info> {
     #[account(
         mut,
-        seeds = [VAULT_STATE_SEED],
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), asset.as_ref()],
+        bump,
+    )]
+    pub exchange_rate: Account<'info, ExchangeRate>,
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
         bump
     )]
     pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    /// CHECK: Only receives the rent of the executed operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
     #[account(mut)]
     pub caller: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct Managers<'info> {
+#[instruction(salt: [u8; 8])]
+pub struct Treasury<'info> {
     #[account(
         mut,
-        seeds = [VAULT_STATE_SEED],
+        seeds = [VAULT_STATE_SEED, salt.scope()],
         bump
     )]
     pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    /// CHECK: Only receives the rent of the executed operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
     #[account(mut)]
     pub caller: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct TransferAdmin<'info> {
+#[instruction(salt: [u8; 8], asset: Pubkey)]
+pub struct SetFees<'info> {
     #[account(
         mut,
-        seeds = [VAULT_STATE_SEED],
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), asset.as_ref()],
+        bump,
+    )]
+    pub exchange_rate: Account<'info, ExchangeRate>,
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    /// CHECK: Only receives the rent of the executed operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct SetQuoteSigner<'info> {
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    /// CHECK: Only receives the rent of the executed operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct FreezeHolder<'info> {
+    /// The token program of the vault token
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        seeds = [MINT_SEED, vault_state.scope()],
+        bump,
+        mint::token_program = token_program,
+    )]
+    pub vault_token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = vault_token_mint,
+        token::token_program = token_program,
+    )]
+    pub holder_vault_token: InterfaceAccount<'info, TokenAccount>,
+    /// The caller's roles, only needed when the caller is not the admin
+    #[account(
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), caller.key().as_ref()],
+        bump = caller_role.bump,
+    )]
+    pub caller_role: Option<Account<'info, Role>>,
+    pub caller: Signer<'info>,
+}
+
+impl<'info> FreezeHolder<'info> {
+    pub fn freeze(&self) -> Result<()> {
+        let cpi_accounts = FreezeAccount {
+            account: self.holder_vault_token.to_account_info(),
+            mint: self.vault_token_mint.to_account_info(),
+            authority: self.vault_state.to_account_info(),
+        };
+
+        let seeds: &[&[u8]] = &self.vault_state.signer_seeds();
+        let seeds = &[seeds][..];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            seeds,
+        );
+        token_interface::freeze_account(cpi_ctx)
+    }
+
+    pub fn thaw(&self) -> Result<()> {
+        let cpi_accounts = ThawAccount {
+            account: self.holder_vault_token.to_account_info(),
+            mint: self.vault_token_mint.to_account_info(),
+            authority: self.vault_state.to_account_info(),
+        };
+
+        let seeds: &[&[u8]] = &self.vault_state.signer_seeds();
+        let seeds = &[seeds][..];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            seeds,
+        );
+        token_interface::thaw_account(cpi_ctx)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], account: Pubkey)]
+pub struct Seize<'info> {
+    /// The token program of the vault token
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    /// CHECK: Only receives the rent of the executed operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [MINT_SEED, vault_state.scope()],
+        bump,
+        mint::token_program = token_program,
+    )]
+    pub vault_token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        address = account,
+        token::mint = vault_token_mint,
+        token::token_program = token_program,
+    )]
+    pub holder_vault_token: InterfaceAccount<'info, TokenAccount>,
+    /// Receives the seized balance, the balance is burned when omitted
+    #[account(
+        mut,
+        token::mint = vault_token_mint,
+        token::token_program = token_program,
+    )]
+    pub recovery_vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+}
+
+impl<'info> Seize<'info> {
+    /// Thaws the holder account, moves `amount` out of it as the permanent delegate
+    /// and freezes it again
+    pub fn seize(&self, amount: u64) -> Result<()> {
+        let seeds: &[&[u8]] = &self.vault_state.signer_seeds();
+        let seeds = &[seeds][..];
+
+        token_interface::thaw_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            ThawAccount {
+                account: self.holder_vault_token.to_account_info(),
+                mint: self.vault_token_mint.to_account_info(),
+                authority: self.vault_state.to_account_info(),
+            },
+            seeds,
+        ))?;
+
+        match &self.recovery_vault_token {
+            Some(recovery) => token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    TransferChecked {
+                        from: self.holder_vault_token.to_account_info(),
+                        mint: self.vault_token_mint.to_account_info(),
+                        to: recovery.to_account_info(),
+                        authority: self.vault_state.to_account_info(),
+                    },
+                    seeds,
+                ),
+                amount,
+                self.vault_token_mint.decimals,
+            )?,
+            None => token_interface::burn(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    Burn {
+                        mint: self.vault_token_mint.to_account_info(),
+                        from: self.holder_vault_token.to_account_info(),
+                        authority: self.vault_state.to_account_info(),
+                    },
+                    seeds,
+                ),
+                amount,
+            )?,
+        }
+
+        token_interface::freeze_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            FreezeAccount {
+                account: self.holder_vault_token.to_account_info(),
+                mint: self.vault_token_mint.to_account_info(),
+                authority: self.vault_state.to_account_info(),
+            },
+            seeds,
+        ))
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct Deposit<'info> {
+    pub system_program: Program<'info, System>,
+    /// The token program of the vault token
+    pub token_program: Interface<'info, TokenInterface>,
+    /// The token program of the collateral, either Token or Token-2022
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    /// The program owned collateral
+    #[account(
+        mut,
+        seeds = [TOKEN_ACCOUNT_SEED, vault_state.scope(), collateral_token_mint.key().as_ref()],
+        bump,
+        token::token_program = collateral_token_program,
+    )]
+    pub program_collateral: InterfaceAccount<'info, TokenAccount>,
+    /// The caller owned collateral
+    #[account(
+        mut,
+        token::mint = collateral_token_mint,
+        token::authority = minter,
+        token::token_program = collateral_token_program,
+    )]
+    pub caller_collateral: InterfaceAccount<'info, TokenAccount>,
+    /// The vault token account that receives the minted tokens, owned by the
+    /// caller unless depositing for someone else
+    #[account(
+        mut,
+        token::mint = vault_token_mint,
+        token::token_program = token_program,
+    )]
+    pub caller_vault_token: InterfaceAccount<'info, TokenAccount>,
+    /// The treasury vault token account, only needed when the asset charges a fee
+    #[account(
+        mut,
+        address = vault_state.treasury @ MintError::InvalidTreasury,
+    )]
+    pub treasury_vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), collateral_token_mint.key().as_ref()],
+        bump,
+    )]
+    pub exchange_rate: Account<'info, ExchangeRate>,
+    /// CHECK: Must match the feed linked in the exchange rate, only read in oracle mode
+    pub oracle: Option<UncheckedAccount<'info>>,
+    /// CHECK: The instructions sysvar, only needed to verify a signed quote
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, vault_state.scope(), minter.key().as_ref()],
+        bump
+    )]
+    pub user_permissions: Account<'info, Permissions>,
+    #[account(
+        mut,
+        constraint = vault_token_mint.key() == vault_state.vault_token_mint,
+        mint::token_program = token_program,
+        seeds = [MINT_SEED, vault_state.scope()],
+        bump
+    )]
+    pub vault_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// The collateral token mint address,
+    /// we dont need any contraints here becauase we also need an exchange rate address
+    /// that is owned by this program and associated with this mint
+    #[account(
+        mut,
+        mint::token_program = collateral_token_program,
+    )]
+    pub collateral_token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(mut)]
+    pub minter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct Redeem<'info> {
+    pub system_program: Program<'info, System>,
+    /// The token program of the vault token
+    pub token_program: Interface<'info, TokenInterface>,
+    /// The token program of the collateral, either Token or Token-2022
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    /// The program owned collateral
+    #[account(
+        mut,
+        seeds = [TOKEN_ACCOUNT_SEED, vault_state.scope(), collateral_token_mint.key().as_ref()],
+        bump,
+        token::token_program = collateral_token_program,
+    )]
+    pub program_collateral: InterfaceAccount<'info, TokenAccount>,
+    /// The collateral account that receives the redeemed collateral, owned by the
+    /// caller unless redeeming to someone else
+    #[account(
+        mut,
+        token::mint = collateral_token_mint,
+        token::token_program = collateral_token_program,
+    )]
+    pub caller_collateral: InterfaceAccount<'info, TokenAccount>,
+    /// The caller owned vault token account
+    #[account(
+        mut,
+        token::mint = vault_token_mint,
+        token::authority = redeemer,
+        token::token_program = token_program,
+    )]
+    pub caller_vault_token: InterfaceAccount<'info, TokenAccount>,
+    /// The treasury vault token account, only needed when the asset charges a fee
+    #[account(
+        mut,
+        address = vault_state.treasury @ MintError::InvalidTreasury,
+    )]
+    pub treasury_vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), collateral_token_mint.key().as_ref()],
+        bump,
+    )]
+    pub exchange_rate: Account<'info, ExchangeRate>,
+    /// CHECK: Must match the feed linked in the exchange rate, only read in oracle mode
+    pub oracle: Option<UncheckedAccount<'info>>,
+    /// CHECK: The instructions sysvar, only needed to verify a signed quote
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, vault_state.scope(), redeemer.key().as_ref()],
+        bump
+    )]
+    pub user_permissions: Account<'info, Permissions>,
+    #[account(
+        mut,
+        constraint = vault_token_mint.key() == vault_state.vault_token_mint,
+        mint::token_program = token_program,
+        seeds = [MINT_SEED, vault_state.scope()],
+        bump,
+    )]
+    pub vault_token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(mut)]
+    pub redeemer: Signer<'info>,
+    /// The collateral token mint address,
+    /// we dont need any contraints here becauase we also need an exchange rate address
+    /// that is owned by this program and associated with this mint
+    #[account(
+        mut,
+        mint::token_program = collateral_token_program,
+    )]
+    pub collateral_token_mint: InterfaceAccount<'info, Mint>,
+}
+
+impl<'info> Deposit<'info> {
+    pub fn check_caller_vault_token(&self) -> Result<()> {
+        if self.caller_vault_token.owner != self.minter.key() {
+            return Err(MintError::NotCallerTokenAccount.into());
+        }
+
+        Ok(())
+    }
+
+    /// Takes the collateral and mints vault tokens at the exchange rate into the
+    /// caller vault token account. Returns the collateral received and the fee.
+    pub fn process_deposit(
+        &mut self,
+        collat: u64,
+        min_amount_out: u64,
+        expiry_slot: Option<u64>,
+    ) -> Result<(u64, u64)> {
+        if (self.vault_state.paused | self.exchange_rate.paused) & PAUSE_MINT != 0 {
+            return Err(MintError::Paused.into());
+        }
+
+        if !self.exchange_rate.status.can_mint() {
+            return Err(MintError::DepositsDisabled.into());
+        }
+
+        check_expiry(expiry_slot)?;
+
+        let (deposit_rate, _) = self.exchange_rate.current_rates(self.oracle.as_deref())?;
+        if deposit_rate == 0 {
+            return Err(MintError::AssetNotSupported.into());
+        }
+
+        if !self.user_permissions.can_mint {
+            return Err(MintError::NotAnApprovedMinter.into());
+        }
+
+        // Mint against what was received, which is less than collat when the mint charges a transfer fee
+        let received = self.transfer_collateral_to_vault(collat)?;
+
+        let amt: u64 = vault_tokens_for(received as u128, deposit_rate, self.exchange_rate.decimals)?
+            .try_into()
+            .map_err(|_| MintError::MathOverflow)?;
+
+        if amt == 0 {
+            return Err(MintError::AmountTooSmall.into());
+        }
+
+        self.consume_mint_limits(amt)?;
+
+        let fee = bps_of(amt, self.exchange_rate.mint_fee_bps);
+        if amt - fee < min_amount_out {
+            return Err(MintError::SlippageExceeded.into());
+        }
+
+        // Mint tokens to the receiver
+        self.mint_vault_tokens(self.caller_vault_token.to_account_info(), amt - fee)?;
+
+        // Mint the fee to the treasury
+        if fee > 0 {
+            let treasury = self.treasury_vault_token.as_ref().ok_or(MintError::InvalidTreasury)?;
+            self.mint_vault_tokens(treasury.to_account_info(), fee)?;
+        }
+
+        Ok((received, fee))
+    }
+
+    /// Transfers collateral from the caller and returns what the program actually received
+    pub fn transfer_collateral_to_vault(
+        &mut self,
+        amount: u64,
+    ) -> Result<u64> {
+        let balance_before = self.program_collateral.amount;
+        let transfer_instruction = TransferChecked {
+            from: self.caller_collateral.to_account_info(),
+            mint: self.collateral_token_mint.to_account_info(),
+            to: self.program_collateral.to_account_info(),
+            authority: self.minter.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(
+            self.collateral_token_program.to_account_info(),
+            transfer_instruction,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, self.collateral_token_mint.decimals)?;
+
+        self.program_collateral.reload()?;
+        let received = self.program_collateral.amount - balance_before;
+        self.exchange_rate.total_deposited = self.exchange_rate.total_deposited.saturating_add(received);
+
+        Ok(received)
+    }
+
+    pub fn consume_mint_limits(
+        &mut self,
+        amount: u64,
+    ) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let window = self.vault_state.limit_window;
+        if !self.vault_state.mint_limit.consume(amount, slot, window)
+            || !self.exchange_rate.mint_limit.consume(amount, slot, window)
+        {
+            return Err(MintError::MaxMintExceeded.into());
+        }
+
+        if !self.user_permissions.consume_mint(amount, slot) {
+            return Err(MintError::UserMintQuotaExceeded.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn mint_vault_tokens(
+        &self,
+        to: AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = MintTo {
+            mint: self.vault_token_mint.to_account_info(),
+            to,
+            authority: self.vault_state.to_account_info(),
+        };
+
+        let seeds: &[&[u8]] = &self.vault_state.signer_seeds();
+        let seeds = &[seeds][..];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            seeds,
+        );
+        token_interface::mint_to(cpi_ctx, amount)
+    }
+}
+
+impl<'info> Redeem<'info> {
+    pub fn check_caller_collateral(&self) -> Result<()> {
+        if self.caller_collateral.owner != self.redeemer.key() {
+            return Err(MintError::NotCallerTokenAccount.into());
+        }
+
+        Ok(())
+    }
+
+    /// Burns vault tokens of the caller and pays out collateral at the exchange rate
+    /// to the caller collateral account. Returns the fee.
+    pub fn process_redeem(
+        &mut self,
+        amt: u64,
+        min_amount_out: u64,
+        expiry_slot: Option<u64>,
+    ) -> Result<u64> {
+        let (fee, collat) = quote_redeem(
+            &self.vault_state,
+            &self.exchange_rate,
+            &self.user_permissions,
+            self.oracle.as_deref(),
+            amt,
+            min_amount_out,
+            expiry_slot,
+        )?;
+
+        self.consume_redeem_limits(amt)?;
+
+        // Check slippage against what was received, which is less than collat when the
+        // mint charges a transfer fee
+        let received = self.transfer_collateral_to_caller(collat)?;
+        if received < min_amount_out {
+            return Err(MintError::SlippageExceeded.into());
+        }
+
+        // Move the fee to the treasury
+        if fee > 0 {
+            self.transfer_fee_to_treasury(fee)?;
+        }
+
+        // Burn staked tokens that caller redeemed
+        self.burn_vault_tokens(amt - fee)?;
+
+        Ok(fee)
+    }
+
+    pub fn consume_redeem_limits(
+        &mut self,
+        amount: u64,
+    ) -> Result<()> {
+        consume_redeem_limits(
+            &mut self.vault_state,
+            &mut self.exchange_rate,
+            &mut self.user_permissions,
+            amount,
+        )
+    }
+
+    /// Transfers collateral to the receiver and returns what the receiver actually received
+    pub fn transfer_collateral_to_caller(
+        &mut self,
+        amount: u64,
+    ) -> Result<u64> {
+        let balance_before = self.caller_collateral.amount;
+        let transfer_instruction = TransferChecked {
+            from: self.program_collateral.to_account_info(),
+            mint: self.collateral_token_mint.to_account_info(),
+            to: self.caller_collateral.to_account_info(),
+            authority: self.vault_state.to_account_info(),
+        };
+
+        let seeds: &[&[u8]] = &self.vault_state.signer_seeds();
+        let seeds = &[seeds][..];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.collateral_token_program.to_account_info(),
+            transfer_instruction,
+            seeds,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, self.collateral_token_mint.decimals)?;
+
+        self.caller_collateral.reload()?;
+        let received = self.caller_collateral.amount - balance_before;
+        self.exchange_rate.total_redeemed = self.exchange_rate.total_redeemed.saturating_add(received);
+
+        Ok(received)
+    }
+
+    pub fn transfer_fee_to_treasury(
+        &self,
+        fee: u64,
+    ) -> Result<()> {
+        let treasury = self.treasury_vault_token.as_ref().ok_or(MintError::InvalidTreasury)?;
+        let transfer_instruction = TransferChecked {
+            from: self.caller_vault_token.to_account_info(),
+            mint: self.vault_token_mint.to_account_info(),
+            to: treasury.to_account_info(),
+            authority: self.redeemer.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            transfer_instruction,
+        );
+        token_interface::transfer_checked(cpi_ctx, fee, self.vault_token_mint.decimals)
+    }
+
+    pub fn burn_vault_tokens(
+        &self,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Burn {
+            mint: self.vault_token_mint.to_account_info(),
+            from: self.caller_vault_token.to_account_info(),
+            authority: self.redeemer.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::burn(cpi_ctx, amount)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct RequestRedeem<'info> {
+    pub system_program: Program<'info, System>,
+    /// The token program of the vault token
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// The caller owned vault token account
+    #[account(
+        mut,
+        token::mint = vault_token_mint,
+        token::authority = redeemer,
+        token::token_program = token_program,
+    )]
+    pub caller_vault_token: InterfaceAccount<'info, TokenAccount>,
+    /// The treasury vault token account, only needed when the asset charges a fee
+    #[account(
+        mut,
+        address = vault_state.treasury @ MintError::InvalidTreasury,
+    )]
+    pub treasury_vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Holds the vault tokens of open redeem tickets
+    #[account(
+        init_if_needed,
+        payer = redeemer,
+        seeds = [REDEEM_ESCROW_SEED, vault_state.key().as_ref()],
+        bump,
+        token::mint = vault_token_mint,
+        token::authority = vault_state,
+        token::token_program = token_program,
+    )]
+    pub redeem_escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), collateral_token_mint.key().as_ref()],
+        bump,
+    )]
+    pub exchange_rate: Account<'info, ExchangeRate>,
+    #[account(
+        init,
+        payer = redeemer,
+        space = 8 + 8 + 32 + 32 + 8 + 8 + 1 + 1,
+        seeds = [
+            REDEEM_TICKET_SEED,
+            vault_state.key().as_ref(),
+            collateral_token_mint.key().as_ref(),
+            exchange_rate.next_ticket.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub ticket: Account<'info, RedeemTicket>,
+    /// CHECK: Must match the feed linked in the exchange rate, only read in oracle mode
+    pub oracle: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, vault_state.scope(), redeemer.key().as_ref()],
+        bump
+    )]
+    pub user_permissions: Account<'info, Permissions>,
+    #[account(
+        constraint = vault_token_mint.key() == vault_state.vault_token_mint,
+        mint::token_program = token_program,
+        seeds = [MINT_SEED, vault_state.scope()],
+        bump,
+    )]
+    pub vault_token_mint: InterfaceAccount<'info, Mint>,
+    pub collateral_token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(mut)]
+    pub redeemer: Signer<'info>,
+}
+
+impl<'info> RequestRedeem<'info> {
+    pub fn transfer_fee_to_treasury(
+        &self,
+        fee: u64,
+    ) -> Result<()> {
+        let treasury = self.treasury_vault_token.as_ref().ok_or(MintError::InvalidTreasury)?;
+        let transfer_instruction = TransferChecked {
+            from: self.caller_vault_token.to_account_info(),
+            mint: self.vault_token_mint.to_account_info(),
+            to: treasury.to_account_info(),
+            authority: self.redeemer.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            transfer_instruction,
+        );
+        token_interface::transfer_checked(cpi_ctx, fee, self.vault_token_mint.decimals)
+    }
+
+    /// Moves vault tokens of the caller into escrow until the ticket is fulfilled or cancelled
+    pub fn escrow_vault_tokens(
+        &self,
+        amount: u64,
+    ) -> Result<()> {
+        let transfer_instruction = TransferChecked {
+            from: self.caller_vault_token.to_account_info(),
+            mint: self.vault_token_mint.to_account_info(),
+            to: self.redeem_escrow.to_account_info(),
+            authority: self.redeemer.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            transfer_instruction,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, self.vault_token_mint.decimals)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct FulfilRedeem<'info> {
+    pub system_program: Program<'info, System>,
+    /// The token program of the vault token
+    pub token_program: Interface<'info, TokenInterface>,
+    /// The token program of the collateral, either Token or Token-2022
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+        mut,
+        seeds = [
+            REDEEM_TICKET_SEED,
+            vault_state.key().as_ref(),
+            collateral_token_mint.key().as_ref(),
+            ticket.id.to_le_bytes().as_ref(),
+        ],
+        bump = ticket.bump,
+    )]
+    pub ticket: Account<'info, RedeemTicket>,
+    /// CHECK: Only receives the rent of a skipped cancelled ticket
+    #[account(mut, address = ticket.owner)]
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), collateral_token_mint.key().as_ref()],
+        bump,
+    )]
+    pub exchange_rate: Account<'info, ExchangeRate>,
+    #[account(
+        mut,
+        seeds = [REDEEM_ESCROW_SEED, vault_state.key().as_ref()],
+        bump,
+        token::token_program = token_program,
+    )]
+    pub redeem_escrow: InterfaceAccount<'info, TokenAccount>,
+    /// Holds the collateral of fulfilled tickets until it is claimed
+    #[account(
+        init_if_needed,
+        payer = caller,
+        seeds = [REDEEM_RESERVE_SEED, vault_state.key().as_ref(), collateral_token_mint.key().as_ref()],
+        bump,
+        token::mint = collateral_token_mint,
+        token::authority = vault_state,
+        token::token_program = collateral_token_program,
+    )]
+    pub redeem_reserve: InterfaceAccount<'info, TokenAccount>,
+    /// The caller owned collateral used to fund the ticket
+    #[account(
+        mut,
+        token::mint = collateral_token_mint,
+        token::authority = caller,
+        token::token_program = collateral_token_program,
+    )]
+    pub caller_collateral: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = vault_token_mint.key() == vault_state.vault_token_mint,
+        mint::token_program = token_program,
+        seeds = [MINT_SEED, vault_state.scope()],
+        bump,
+    )]
+    pub vault_token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mint::token_program = collateral_token_program,
+    )]
+    pub collateral_token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    /// The caller's roles
+    #[account(
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), caller.key().as_ref()],
+        bump = caller_role.bump,
+    )]
+    pub caller_role: Account<'info, Role>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct ClaimRedeem<'info> {
+    /// The token program of the collateral, either Token or Token-2022
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner @ MintError::NotTicketOwner,
+        seeds = [
+            REDEEM_TICKET_SEED,
+            vault_state.key().as_ref(),
+            collateral_token_mint.key().as_ref(),
+            ticket.id.to_le_bytes().as_ref(),
+        ],
+        bump = ticket.bump,
+    )]
+    pub ticket: Account<'info, RedeemTicket>,
+    #[account(
+        mut,
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), collateral_token_mint.key().as_ref()],
+        bump,
+    )]
+    pub exchange_rate: Account<'info, ExchangeRate>,
+    #[account(
+        mut,
+        seeds = [REDEEM_RESERVE_SEED, vault_state.key().as_ref(), collateral_token_mint.key().as_ref()],
+        bump,
+        token::token_program = collateral_token_program,
+    )]
+    pub redeem_reserve: InterfaceAccount<'info, TokenAccount>,
+    /// The owner's collateral token account
+    #[account(
+        mut,
+        token::mint = collateral_token_mint,
+        token::token_program = collateral_token_program,
+    )]
+    pub owner_collateral: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mint::token_program = collateral_token_program,
+    )]
+    pub collateral_token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct CancelRedeem<'info> {
+    /// The token program of the vault token
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+        mut,
+        has_one = owner @ MintError::NotTicketOwner,
+        seeds = [REDEEM_TICKET_SEED, vault_state.key().as_ref(), ticket.asset.as_ref(), ticket.id.to_le_bytes().as_ref()],
+        bump = ticket.bump,
+    )]
+    pub ticket: Account<'info, RedeemTicket>,
+    #[account(
+        mut,
+        seeds = [REDEEM_ESCROW_SEED, vault_state.key().as_ref()],
+        bump,
+        token::token_program = token_program,
+    )]
+    pub redeem_escrow: InterfaceAccount<'info, TokenAccount>,
+    /// The owner's vault token account
+    #[account(
+        mut,
+        token::mint = vault_token_mint,
+        token::token_program = token_program,
+    )]
+    pub owner_vault_token: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        constraint = vault_token_mint.key() == vault_state.vault_token_mint,
+        mint::token_program = token_program,
+        seeds = [MINT_SEED, vault_state.scope()],
+        bump,
+    )]
+    pub vault_token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct Repay<'info> {
+    /// The token program of the collateral, either Token or Token-2022
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    /// The program owned collateral
+    #[account(
+        mut,
+        seeds = [TOKEN_ACCOUNT_SEED, vault_state.scope(), collateral_token_mint.key().as_ref()],
+        bump,
+        token::token_program = collateral_token_program,
+    )]
+    pub program_collateral: InterfaceAccount<'info, TokenAccount>,
+    /// The caller owned collateral
+    #[account(
+        mut,
+        token::mint = collateral_token_mint,
+        token::authority = caller,
+        token::token_program = collateral_token_program,
+    )]
+    pub caller_collateral: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), collateral_token_mint.key().as_ref()],
+        bump,
+    )]
+    pub exchange_rate: Account<'info, ExchangeRate>,
+    #[account(
+        mint::token_program = collateral_token_program,
+    )]
+    pub collateral_token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    /// The caller's roles
+    #[account(
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), caller.key().as_ref()],
+        bump = caller_role.bump,
+    )]
+    pub caller_role: Account<'info, Role>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct Backing<'info> {
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        constraint = vault_token_mint.key() == vault_state.vault_token_mint,
+        seeds = [MINT_SEED, vault_state.scope()],
+        bump,
+    )]
+    pub vault_token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [ASSET_REGISTRY_SEED, vault_state.key().as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, AssetRegistry>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], destination: Pubkey)]
+pub struct SetWithdrawPolicy<'info> {
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        seeds = [WITHDRAW_POLICY_SEED, vault_state.key().as_ref(), destination.as_ref()],
+        bump = policy.bump,
+    )]
+    pub policy: Account<'info, WithdrawPolicy>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    /// CHECK: Only receives the rent of the executed operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], destination: Pubkey)]
+pub struct TightenWithdrawPolicy<'info> {
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        seeds = [WITHDRAW_POLICY_SEED, vault_state.key().as_ref(), destination.as_ref()],
+        bump = policy.bump,
+    )]
+    pub policy: Account<'info, WithdrawPolicy>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct SetWithdrawThreshold<'info> {
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    /// CHECK: Only receives the rent of the executed operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], asset: Pubkey, amount: u64, destination: Pubkey)]
+pub struct ProposeWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        init,
+        payer = caller,
+        space = 8 + 8 + 32 + 8 + 32 + 32 + (4 + MAX_WITHDRAW_APPROVALS * 32) + 8 + 1,
+        seeds = [WITHDRAW_PROPOSAL_SEED, vault_state.key().as_ref(), vault_state.withdraw_proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+    /// Only whitelisted destinations have a policy
+    #[account(
+        seeds = [WITHDRAW_POLICY_SEED, vault_state.key().as_ref(), destination.as_ref()],
+        bump = policy.bump,
+    )]
+    pub policy: Account<'info, WithdrawPolicy>,
+    /// The caller's roles
+    #[account(
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), caller.key().as_ref()],
+        bump = caller_role.bump,
+    )]
+    pub caller_role: Account<'info, Role>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct ApproveWithdraw<'info> {
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        seeds = [WITHDRAW_PROPOSAL_SEED, vault_state.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+    /// The caller's roles
+    #[account(
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), caller.key().as_ref()],
+        bump = caller_role.bump,
+    )]
+    pub caller_role: Account<'info, Role>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct CancelWithdraw<'info> {
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [WITHDRAW_PROPOSAL_SEED, vault_state.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+    /// CHECK: Only receives the rent of the closed proposal
+    #[account(mut, address = proposal.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct Withdraw<'info> {
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+        mut,
+        seeds = [TOKEN_ACCOUNT_SEED, vault_state.scope(), collat_mint.key().as_ref()],
+        bump,
+        token::token_program = token_program,
+    )]
+    pub program_collat: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.key() == proposal.destination @ MintError::ProposalMismatch,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = collat_mint.key() == proposal.asset @ MintError::ProposalMismatch,
+    )]
+    pub collat_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [EXCHANGE_RATE_SEED, vault_state.scope(), collat_mint.key().as_ref()],
+        bump,
+    )]
+    pub exchange_rate: Account<'info, ExchangeRate>,
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [WITHDRAW_PROPOSAL_SEED, vault_state.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+    /// CHECK: Only receives the rent of the closed proposal
+    #[account(mut, address = proposal.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [WITHDRAW_POLICY_SEED, vault_state.key().as_ref(), destination.key().as_ref()],
+        bump = policy.bump,
+    )]
+    pub policy: Account<'info, WithdrawPolicy>,
+    /// The caller's roles
+    #[account(
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), caller.key().as_ref()],
+        bump = caller_role.bump,
+    )]
+    pub caller_role: Account<'info, Role>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], user: Pubkey)]
+pub struct UserPermissions<'info> {
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        init_if_needed, 
+        space = Permissions::SPACE,
+        payer = caller,
+        seeds = [VAULT_STATE_SEED, vault_state.scope(), user.as_ref()],
+        bump
+    )]
+    pub user_permissions: Account<'info, Permissions>,
+    /// The caller's roles
+    #[account(
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), caller.key().as_ref()],
+        bump = caller_role.bump,
+    )]
+    pub caller_role: Account<'info, Role>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], user: Pubkey)]
+pub struct ClosePermissions<'info> {
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = rent_receiver,
+        seeds = [VAULT_STATE_SEED, vault_state.scope(), user.as_ref()],
+        bump
+    )]
+    pub user_permissions: Account<'info, Permissions>,
+    /// CHECK: Receives the rent of the closed permissions
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+    /// The caller's roles
+    #[account(
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), caller.key().as_ref()],
+        bump = caller_role.bump,
+    )]
+    pub caller_role: Account<'info, Role>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], user: Pubkey)]
+pub struct RevokeAll<'info> {
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, vault_state.scope(), user.as_ref()],
+        bump
+    )]
+    pub user_permissions: Account<'info, Permissions>,
+    /// CHECK: Receives the rent if the permissions are closed
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+    /// The caller's roles
+    #[account(
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), caller.key().as_ref()],
+        bump = caller_role.bump,
+    )]
+    pub caller_role: Account<'info, Role>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], address: Pubkey)]
+pub struct WithdrawAddresses<'info> {
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = caller,
+        seeds = [WITHDRAW_POLICY_SEED, vault_state.key().as_ref(), address.as_ref()],
+        bump = policy.bump,
+    )]
+    pub policy: Account<'info, WithdrawPolicy>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], address: Pubkey)]
+pub struct AddWithdrawAddress<'info> {
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    /// CHECK: Only receives the rent of the executed operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = caller,
+        space = 8 + 32 + (4 + MAX_POLICY_ASSETS * 32) + 8 + 24 + 1,
+        seeds = [WITHDRAW_POLICY_SEED, vault_state.key().as_ref(), address.as_ref()],
+        bump
+    )]
+    pub policy: Account<'info, WithdrawPolicy>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], holder: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = 8 + 32 + 1 + 1,
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), holder.as_ref()],
+        bump
+    )]
+    pub role: Account<'info, Role>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    /// CHECK: Only receives the rent of the executed operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], holder: Pubkey)]
+pub struct RevokeRole<'info> {
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), holder.as_ref()],
+        bump = role.bump,
+    )]
+    pub role: Account<'info, Role>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8], holder: Pubkey)]
+pub struct CloseRole<'info> {
+    #[account(
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = caller,
+        seeds = [ROLE_SEED, vault_state.key().as_ref(), holder.as_ref()],
+        bump = role.bump,
+    )]
+    pub role: Account<'info, Role>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [TIMELOCK_SEED, vault_state.key().as_ref(), operation.id.to_le_bytes().as_ref()],
+        bump = operation.bump,
+    )]
+    pub operation: Account<'info, TimelockOperation>,
+    /// CHECK: Only receives the rent of the executed operation
+    #[account(mut, address = operation.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(salt: [u8; 8])]
+pub struct TransferAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [VAULT_STATE_SEED, salt.scope()],
         bump
     )]
     pub vault_state: Account<'info, VaultState>,
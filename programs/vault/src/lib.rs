@@ -1,10 +1,16 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
 use anchor_spl::{
-    token::{self, MintTo, Transfer, Burn},
+    token_interface::{self, MintTo, TokenAccount, TransferChecked, Burn, CloseAccount, FreezeAccount, ThawAccount},
     metadata::{
         create_metadata_accounts_v3,
         mpl_token_metadata::types::DataV2,
         CreateMetadataAccountsV3, 
+        update_metadata_accounts_v2,
+        UpdateMetadataAccountsV2,
         Metadata,
     }, 
 };
@@ -14,12 +20,53 @@ use context::*;
 declare_id!("A3p6U1p5jjZQbu346LrJb1asrTjkEPhDkfH4CXCYgpEd");
 
 const DECIMALS_SCALAR: u128 = 1_000_000_000;
-const MAX_WITHDRAW_ADRESSES: usize = 50;
-const MAX_MANAGER_ADDRESSES: usize = 20;
-const MINT_SEED: &[u8] = b"mint";
-const TOKEN_ACCOUNT_SEED: &[u8] = b"token-account";
-const EXCHANGE_RATE_SEED: &[u8] = b"exchange-rate";
-const VAULT_STATE_SEED: &[u8] = b"vault-state";
+const BPS_SCALAR: u128 = 10_000;
+const MAX_PRICE_DECIMALS: u8 = 18;
+#[cfg(not(feature = "short-timelock"))]
+const MIN_TIMELOCK_DELAY: u32 = 24 * 3600;
+#[cfg(feature = "short-timelock")]
+const MIN_TIMELOCK_DELAY: u32 = 0;
+const VAULT_TOKEN_DECIMALS: u8 = 9;
+const MAX_COLLATERAL_DECIMALS: u8 = 18;
+const MAX_TIMELOCK_DELAY: u32 = 30 * 24 * 3600;
+const MIN_WITHDRAW_THRESHOLD: u8 = 2;
+const MAX_FEE_BPS: u16 = 1_000;
+const DEFAULT_MAX_RATE_CHANGE_BPS: u16 = 500;
+const MAX_WITHDRAW_PROPOSAL_TTL: i64 = 7 * 24 * 3600;
+const MAX_WITHDRAW_APPROVALS: usize = 20;
+const MAX_POLICY_ASSETS: usize = 10;
+const WITHDRAW_POLICY_WINDOW: u64 = 24 * 3600;
+const PAUSE_MINT: u8 = 1 << 0;
+const PAUSE_REDEEM: u8 = 1 << 1;
+const PAUSE_WITHDRAW: u8 = 1 << 2;
+const PAUSE_ALL: u8 = PAUSE_MINT | PAUSE_REDEEM | PAUSE_WITHDRAW;
+const ROLE_ASSET_MANAGER: u8 = 1 << 0;
+const ROLE_ROLE_MANAGER: u8 = 1 << 1;
+const ROLE_GUARDIAN: u8 = 1 << 2;
+const ROLE_ALL: u8 = ROLE_ASSET_MANAGER | ROLE_ROLE_MANAGER | ROLE_GUARDIAN;
+pub const MINT_SEED: &[u8] = b"mint";
+pub const TOKEN_ACCOUNT_SEED: &[u8] = b"token-account";
+pub const EXCHANGE_RATE_SEED: &[u8] = b"exchange-rate";
+pub const VAULT_STATE_SEED: &[u8] = b"vault-state";
+pub const TIMELOCK_SEED: &[u8] = b"timelock";
+pub const WITHDRAW_PROPOSAL_SEED: &[u8] = b"withdraw-proposal";
+pub const ROLE_SEED: &[u8] = b"role";
+pub const ASSET_REGISTRY_SEED: &[u8] = b"asset-registry";
+const ASSET_ENTRY_SIZE: usize = 32 + 32 + 1 + 1;
+pub const WITHDRAW_POLICY_SEED: &[u8] = b"withdraw-policy";
+pub const REDEEM_TICKET_SEED: &[u8] = b"redeem-ticket";
+pub const REDEEM_ESCROW_SEED: &[u8] = b"redeem-escrow";
+pub const REDEEM_RESERVE_SEED: &[u8] = b"redeem-reserve";
+/// Reserved for the vault created before instances were salted, see [`VaultScope`]. Its
+/// vault token predates the freeze authority, the permanent delegate and the mutable
+/// metadata, so freezing, seizing and metadata updates are rejected for it
+pub const LEGACY_SALT: [u8; 8] = [0; 8];
+/// Account sizes of the legacy vault, a migrated account no longer matches them
+const LEGACY_VAULT_STATE_SPACE: usize = 8 + 8 + (2 + 20 + 20 + 50) * 32;
+const LEGACY_EXCHANGE_RATE_SPACE: usize = 8 + 8 + 8 + 32;
+const LEGACY_PERMISSIONS_SPACE: usize = 8 + 32 + 1 + 1;
+/// Prefixes every signed quote message so the signature can't be replayed elsewhere
+const QUOTE_DOMAIN: &[u8] = b"solomon-vault-quote";
 
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
 pub struct MetadataParams {
@@ -28,8 +75,95 @@ pub struct MetadataParams {
     pub uri: String,
 }
 
+impl MetadataParams {
+    pub fn into_data(self) -> DataV2 {
+        DataV2 {
+            name: self.name,
+            symbol: self.symbol,
+            uri: self.uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        }
+    }
+}
+
+/// The seed a vault contributes to the addresses of the accounts that predate salted
+/// instances: its vault state, mint, program collateral, exchange rates and permissions.
+/// The legacy vault contributes none so those accounts keep their addresses
+pub trait VaultScope {
+    fn scope(&self) -> &[u8];
+}
+
+impl VaultScope for [u8; 8] {
+    fn scope(&self) -> &[u8] {
+        if *self == LEGACY_SALT {
+            &[]
+        } else {
+            self
+        }
+    }
+}
+
+impl<'info> VaultScope for Account<'info, VaultState> {
+    fn scope(&self) -> &[u8] {
+        vault_scope(&self.salt, AsRef::<AccountInfo>::as_ref(self).key)
+    }
+}
+
+/// The scope of the vault state at `vault_state`, keyed by `salt`
+pub fn vault_scope<'a>(salt: &[u8; 8], vault_state: &'a Pubkey) -> &'a [u8] {
+    if *salt == LEGACY_SALT {
+        &[]
+    } else {
+        vault_state.as_ref()
+    }
+}
+
 #[account]
 pub struct VaultState {
+    pub vault_token_mint: Pubkey,
+    pub admin: Pubkey,
+    /// The proposed admin, takes over once it accepts
+    pub pending_admin: Pubkey,
+    pub bump: u8,
+    /// The length in slots of a mint / redeem limit window, never zero once set_limits ran
+    pub limit_window: u64,
+    /// Vault token units that can be minted per window across all assets, zero means uncapped
+    pub mint_limit: WindowLimit,
+    /// Vault token units that can be redeemed per window across all assets, zero means uncapped
+    pub redeem_limit: WindowLimit,
+    /// Seconds a queued operation must wait before it can be executed
+    pub timelock_delay: u32,
+    /// The id of the next queued operation
+    pub operation_count: u64,
+    /// Asset manager approvals needed to execute a withdraw proposal
+    pub withdraw_threshold: u8,
+    /// The id of the next withdraw proposal
+    pub withdraw_proposal_count: u64,
+    /// Bitmask of PAUSE_* flags that apply to every asset
+    pub paused: u8,
+    /// The vault token account that receives mint and redeem fees
+    pub treasury: Pubkey,
+    /// The off-chain pricing key that signs quotes, the default key disables quotes
+    pub quote_signer: Pubkey,
+    /// Tells this vault instance apart from the others of the program
+    pub salt: [u8; 8],
+}
+
+impl VaultState {
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 1 + 8 + 2 * 24 + 4 + 8 + 1 + 8 + 1 + 32 + 32 + 8;
+
+    /// The seeds to sign with as this vault state
+    pub fn signer_seeds(&self) -> [&[u8]; 3] {
+        [VAULT_STATE_SEED, self.salt.scope(), std::slice::from_ref(&self.bump)]
+    }
+}
+
+/// The vault state as laid out before instances were salted, only read by migrate_vault_state
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyVaultState {
     pub vault_token_mint: Pubkey,
     pub asset_managers: Vec<Pubkey>,
     pub role_managers: Vec<Pubkey>,
@@ -38,21 +172,276 @@ pub struct VaultState {
     pub bump: u8,
 }
 
+impl LegacyVaultState {
+    pub fn read(data: &[u8]) -> Result<Self> {
+        Ok(Self::deserialize(&mut &data[8..])?)
+    }
+
+    /// The vault state in the current layout, with the defaults of a new vault
+    pub fn migrate(&self, timelock_delay: u32) -> VaultState {
+        VaultState {
+            vault_token_mint: self.vault_token_mint,
+            admin: self.admin,
+            pending_admin: Pubkey::default(),
+            bump: self.bump,
+            limit_window: 0,
+            mint_limit: WindowLimit::default(),
+            redeem_limit: WindowLimit::default(),
+            timelock_delay,
+            operation_count: 0,
+            withdraw_threshold: MIN_WITHDRAW_THRESHOLD,
+            withdraw_proposal_count: 0,
+            paused: 0,
+            treasury: Pubkey::default(),
+            quote_signer: Pubkey::default(),
+            salt: LEGACY_SALT,
+        }
+    }
+}
+
 #[account]
+#[derive(Default)]
 pub struct ExchangeRate {
-    asset: Pubkey,
+    pub asset: Pubkey,
     /// The deposit rate is defined in scaled units of stable coin per asset coin
     /// (1e9)
-    deposit_rate: u64,
+    pub deposit_rate: u64,
     /// The redeem rate is defined in scaled units of asset coin per stable coin
-    redeem_rate: u64,
+    pub redeem_rate: u64,
+    /// Vault token units that can be minted per window against this asset, zero means uncapped
+    pub mint_limit: WindowLimit,
+    /// Vault token units that can be redeemed per window against this asset, zero means uncapped
+    pub redeem_limit: WindowLimit,
+    /// The price feed the rates are derived from, if any
+    pub oracle: OracleConfig,
+    /// Bitmask of PAUSE_* flags for this asset
+    pub paused: u8,
+    /// Fee on minted vault tokens, taken in vault tokens
+    pub mint_fee_bps: u16,
+    /// Fee on redeemed vault tokens, taken in vault tokens
+    pub redeem_fee_bps: u16,
+    /// The id of the next redeem ticket
+    pub next_ticket: u64,
+    /// The id of the next redeem ticket to fulfil
+    pub next_fulfil: u64,
+    /// Collateral received from deposits
+    pub total_deposited: u64,
+    /// Collateral received by redeemers, net of transfer fees
+    pub total_redeemed: u64,
+    /// Collateral withdrawn to custodians and not repaid yet
+    pub held_externally: u64,
+    /// Collateral of fulfilled tickets waiting to be claimed
+    pub reserved: u64,
+    /// Mirrors the status of the asset in the registry
+    pub status: AssetStatus,
+    /// Max change of either rate per update, zero means uncapped. Listing an asset
+    /// starts it at DEFAULT_MAX_RATE_CHANGE_BPS.
+    pub max_rate_change_bps: u16,
+    /// Min seconds between two rate updates, zero allows back to back updates
+    pub min_rate_interval: u32,
+    /// Unix time of the last rate update
+    pub rates_updated_at: i64,
+    /// The decimals of the asset, cached when it is listed
+    pub decimals: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AssetStatus {
+    #[default]
+    Active,
+    DepositOnly,
+    RedeemOnly,
+    /// Terminal, the asset can no longer be deposited or redeemed
+    Retired,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct AssetEntry {
+    pub mint: Pubkey,
+    /// The exchange rate PDA of the asset
+    pub exchange_rate: Pubkey,
+    pub decimals: u8,
+    pub status: AssetStatus,
+}
+
+/// Every asset ever listed through `update_asset`, grown by one entry per new asset
+#[account]
+pub struct AssetRegistry {
+    pub bump: u8,
+    pub assets: Vec<AssetEntry>,
+}
+
+/// Describes where to find a price in an oracle account and how far it can be trusted.
+/// The price is quoted in vault tokens per asset coin, scaled by `10^price_decimals`.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OracleConfig {
+    /// The oracle account, the default key disables oracle mode
+    pub feed: Pubkey,
+    /// Byte offset of the little endian i64 price
+    pub price_offset: u32,
+    /// Byte offset of the little endian i64 unix publish time
+    pub timestamp_offset: u32,
+    pub price_decimals: u8,
+    /// Max age of a price in seconds, must be non-zero when a feed is set
+    pub max_age: u32,
+    /// Max deviation of the oracle rates from the admin set rates, must be non-zero
+    /// when a feed is set
+    pub max_deviation_bps: u16,
+}
+
+/// A cap on the amount of vault tokens that can flow within a fixed window
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, Default)]
+pub struct WindowLimit {
+    /// Zero means uncapped
+    pub max: u64,
+    pub used: u64,
+    pub window_start: u64,
+}
+
+/// A sensitive admin action that has to go through the timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum TimelockAction {
+    UpdateAsset { asset: Pubkey, deposit_rate: u64, redeem_rate: u64 },
+    AddWithdrawAddress { address: Pubkey },
+    GrantRole { holder: Pubkey, roles: u8 },
+    ProposeAdmin { new_admin: Pubkey },
+    SetTimelockDelay { delay: u32 },
+    SetOracle { asset: Pubkey, config: OracleConfig },
+    SetWithdrawThreshold { threshold: u8 },
+    SetFees { asset: Pubkey, mint_fee_bps: u16, redeem_fee_bps: u16 },
+    SetTreasury { treasury: Pubkey },
+    SetQuoteSigner { signer: Pubkey },
+    SetWithdrawPolicy { destination: Pubkey, allowed_mints: Vec<Pubkey>, max_per_withdraw: u64, daily_limit: u64 },
+    SetRateLimits { asset: Pubkey, max_change_bps: u16, min_interval: u32 },
+    /// Burns `amount` from a frozen holder account, or moves it to `recovery` when set
+    Seize { account: Pubkey, amount: u64, recovery: Option<Pubkey> },
+}
+
+#[account]
+pub struct TimelockOperation {
+    pub id: u64,
+    pub action: TimelockAction,
+    /// Earliest unix time the operation can be executed at
+    pub eta: i64,
+    pub proposer: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+pub struct WithdrawProposal {
+    pub id: u64,
+    /// The collateral mint to withdraw
+    pub asset: Pubkey,
+    pub amount: u64,
+    /// The whitelisted token account receiving the collateral
+    pub destination: Pubkey,
+    pub proposer: Pubkey,
+    /// Asset managers that approved, including the proposer
+    pub approvals: Vec<Pubkey>,
+    /// Unix time after which the proposal can no longer be approved or executed
+    pub deadline: i64,
+    pub bump: u8,
+}
+
+/// The value of the collateral backing the vault token supply
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy)]
+pub struct BackingReport {
+    /// Collateral value in vault token units
+    pub backing: u64,
+    pub supply: u64,
+    /// Backing over supply, zero when nothing is minted
+    pub ratio_bps: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TicketStatus {
+    Pending,
+    Fulfilled,
+    Cancelled,
+}
+
+#[account]
+pub struct RedeemTicket {
+    pub id: u64,
+    /// The collateral mint
+    pub asset: Pubkey,
+    pub owner: Pubkey,
+    /// Escrowed vault tokens, burned once the ticket is fulfilled
+    pub amount: u64,
+    /// Collateral owed, fixed at the redeem rate when the ticket was requested
+    pub collateral: u64,
+    pub status: TicketStatus,
+    pub bump: u8,
+}
+
+/// The roles held by a single key. The admin is not a role, it is the `admin` of the
+/// vault state and moves with the two step handover.
+#[account]
+pub struct Role {
+    pub holder: Pubkey,
+    /// Bitmask of ROLE_* flags
+    pub roles: u8,
+    pub bump: u8,
+}
+
+/// Restricts what a whitelisted withdraw address can receive. The policy account
+/// existing is what whitelists the destination.
+#[account]
+pub struct WithdrawPolicy {
+    /// The whitelisted token account this policy applies to
+    pub destination: Pubkey,
+    /// Collateral mints the destination may receive
+    pub allowed_mints: Vec<Pubkey>,
+    /// Max collateral units per withdraw, zero means uncapped
+    pub max_per_withdraw: u64,
+    /// Collateral units that can be withdrawn per day, zero means uncapped
+    pub daily_limit: WindowLimit,
+    pub bump: u8,
 }
 
 #[account]
 pub struct Permissions {
-    key: Pubkey,
-    can_mint: bool,
-    can_redeem: bool,
+    pub key: Pubkey,
+    pub can_mint: bool,
+    pub can_redeem: bool,
+    /// The highest quote nonce used so far, quotes must use a higher one
+    pub quote_nonce: u64,
+    /// The length in slots of a quota window, zero disables the per window quotas
+    pub quota_window: u64,
+    /// Vault token units the user can mint per quota window, zero means uncapped
+    pub mint_quota: WindowLimit,
+    /// Vault token units the user can redeem per quota window, zero means uncapped
+    pub redeem_quota: WindowLimit,
+    /// Vault token units the user can mint over its lifetime, zero means uncapped
+    pub max_total_mint: u64,
+    /// Vault token units the user can redeem over its lifetime, zero means uncapped
+    pub max_total_redeem: u64,
+    pub total_minted: u64,
+    pub total_redeemed: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteSide {
+    Mint,
+    Redeem,
+}
+
+/// A price quoted off-chain for a single mint or redeem. The quote signer signs
+/// `QUOTE_DOMAIN || vault_state || borsh(quote)` in an Ed25519 instruction placed
+/// right before the deposit or redeem.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct Quote {
+    pub user: Pubkey,
+    /// The collateral mint
+    pub asset: Pubkey,
+    pub side: QuoteSide,
+    /// Collateral sent for a mint, vault tokens sent for a redeem
+    pub amount_in: u64,
+    /// Vault tokens received for a mint, collateral received for a redeem
+    pub amount_out: u64,
+    /// Unix time after which the quote can no longer be filled
+    pub expiry: i64,
+    pub nonce: u64,
 }
 
 #[program]
@@ -61,27 +450,27 @@ pub mod vault {
 
     pub fn initialize_vault_state(
         ctx: Context<InitializeVaultState>,
+        salt: [u8; 8],
         admin: Pubkey,
         metadata: MetadataParams,
+        timelock_delay: u32,
     ) -> Result<()> {
-        let seeds = &[VAULT_STATE_SEED, &[ctx.bumps.vault_state]];
-        let signer = [&seeds[..]];
+        if !(MIN_TIMELOCK_DELAY..=MAX_TIMELOCK_DELAY).contains(&timelock_delay) {
+            return Err(MintError::InvalidTimelockDelay.into());
+        }
 
-        let token_data = DataV2 {
-            name: metadata.name,
-            symbol: metadata.symbol,
-            uri: metadata.uri,
-            seller_fee_basis_points: 0,
-            creators: None,
-            collection: None,
-            uses: None,
-        };
+        if salt == LEGACY_SALT {
+            return Err(MintError::ReservedSalt.into());
+        }
+
+        let seeds = &[VAULT_STATE_SEED, &salt, &[ctx.bumps.vault_state]];
+        let signer = [&seeds[..]];
 
         let metadata_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_metadata_program.to_account_info(),
             CreateMetadataAccountsV3 {
                 payer: ctx.accounts.signer.to_account_info(),
-                update_authority: ctx.accounts.signer.to_account_info(),
+                update_authority: ctx.accounts.vault_state.to_account_info(),
                 mint: ctx.accounts.vault_token.to_account_info(),
                 metadata: ctx.accounts.metadata.to_account_info(),
                 mint_authority: ctx.accounts.vault_state.to_account_info(),
@@ -91,433 +480,2654 @@ pub mod vault {
             &signer
         );
 
+        // The vault state holds the update authority so the metadata can be changed
+        // through update_metadata
         create_metadata_accounts_v3(
             metadata_ctx,
-            token_data,
-            false,
+            metadata.into_data(),
+            true,
             true,
             None,
         )?;
 
         ctx.accounts.vault_state.admin = admin;
         ctx.accounts.vault_state.vault_token_mint = ctx.accounts.vault_token.key();
+        ctx.accounts.vault_state.salt = salt;
         ctx.accounts.vault_state.bump = ctx.bumps.vault_state;
+        ctx.accounts.vault_state.timelock_delay = timelock_delay;
+        ctx.accounts.vault_state.withdraw_threshold = MIN_WITHDRAW_THRESHOLD;
+        ctx.accounts.registry.bump = ctx.bumps.registry;
 
         Ok(())
     }
 
-    // The deposit rate is (stable units / asset units) [how many stable coins a depositer will get per asset coin]
-    // The redeem rate is (asset units / stable units) [how many asset coins a depositer will get per stable coin]
-    pub fn update_asset(
-        ctx: Context<UpdateAsset>,
-        asset: Pubkey,
-        deposit_rate: u64,
-        redeem_rate: u64,
-    ) -> Result<()> {
-        if ctx.accounts.authority.key() != ctx.accounts.vault_state.admin {
-            return Err(MintError::NotAdmin.into());
+    /// Rewrites the vault state created before instances were salted in the current layout.
+    /// It keeps the legacy salt, so the mint, program collateral, exchange rates and
+    /// permissions keep their addresses; the latter two are migrated one by one through
+    /// migrate_exchange_rate and migrate_permissions. Roles and withdraw policies have no
+    /// legacy counterpart, the legacy managers and withdraw addresses are emitted so the
+    /// admin can grant and set them again through the timelock
+    pub fn migrate_vault_state(ctx: Context<MigrateVaultState>, timelock_delay: u32) -> Result<()> {
+        if !(MIN_TIMELOCK_DELAY..=MAX_TIMELOCK_DELAY).contains(&timelock_delay) {
+            return Err(MintError::InvalidTimelockDelay.into());
         }
 
-        ctx.accounts.exchange_rate.asset = asset;
-        ctx.accounts.exchange_rate.deposit_rate = deposit_rate;
-        ctx.accounts.exchange_rate.redeem_rate = redeem_rate;
+        let info = ctx.accounts.vault_state.to_account_info();
+        check_legacy::<VaultState>(&info, LEGACY_VAULT_STATE_SPACE)?;
+        let legacy = LegacyVaultState::read(&info.try_borrow_data()?)?;
 
-        emit!(AssetModifiedEvent{
-            who: ctx.accounts.authority.key(),
-            asset: asset,
-            deposit_rate,
-            redeem_rate,
+        if ctx.accounts.admin.key() != legacy.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        // The legacy layout reserved room for the manager lists, its rent goes to the admin
+        resize_account(
+            &info,
+            VaultState::SPACE,
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        legacy.migrate(timelock_delay).try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        ctx.accounts.registry.bump = ctx.bumps.registry;
+
+        emit!(VaultStateMigratedEvent {
+            vault_state: info.key(),
+            vault_token_mint: legacy.vault_token_mint,
+            admin: legacy.admin,
+            asset_managers: legacy.asset_managers,
+            role_managers: legacy.role_managers,
+            withdraw_addresses: legacy.withdraw_addresses,
         });
 
         Ok(())
     }
 
-    pub fn deposit(ctx: Context<Deposit>, collat: u64) -> Result<()> {
-        let rate = ctx.accounts.exchange_rate.deposit_rate as u128;
-        let decimals = ctx.accounts.collateral_token_mint.decimals;
-        let collat_adjusted =  if decimals < 9 {
-            collat * 10_u64.pow(9 - decimals as u32)
-        } else {
-            collat
-        };
-        let amt: u64 = (collat_adjusted as u128 * rate / DECIMALS_SCALAR).try_into().unwrap();
-
-        if rate == 0 {
-            return Err(MintError::AssetNotSupported.into());
+    /// Grows a legacy exchange rate of the migrated legacy vault to the current layout and
+    /// lists the asset in the registry. The legacy layout is a prefix of the current one,
+    /// so the rates carry over
+    pub fn migrate_exchange_rate(ctx: Context<MigrateExchangeRate>, asset: Pubkey) -> Result<()> {
+        if ctx.accounts.admin.key() != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotAdmin.into());
         }
-        
-        if !ctx.accounts.user_permissions.can_mint {
-            return Err(MintError::NotAnApprovedMinter.into());
+
+        let decimals = ctx.accounts.collateral_token_mint.decimals;
+        if decimals > MAX_COLLATERAL_DECIMALS {
+            return Err(MintError::UnsupportedDecimals.into());
         }
 
-        // Transfer collat to mint vault
-        let transfer_instruction = Transfer {
-            from: ctx.accounts.caller_collateral.to_account_info(),
-            to: ctx.accounts.program_collateral.to_account_info(),
-            authority: ctx.accounts.minter.to_account_info(),
-        };
+        let info = ctx.accounts.exchange_rate.to_account_info();
+        check_legacy::<ExchangeRate>(&info, LEGACY_EXCHANGE_RATE_SPACE)?;
+        resize_account(
+            &info,
+            ExchangeRate::SPACE,
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
 
-        let cpi_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_instruction,
-        );
-        token::transfer(cpi_ctx, collat)?;
+        let mut exchange_rate = ExchangeRate::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        exchange_rate.decimals = decimals;
+        exchange_rate.max_rate_change_bps = DEFAULT_MAX_RATE_CHANGE_BPS;
+        exchange_rate.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
 
-        // Mint tokens to caller
-        let cpi_accounts = MintTo {
-            mint: ctx.accounts.vault_token_mint.to_account_info(),
-            to: ctx.accounts.caller_vault_token.to_account_info(),
-            authority: ctx.accounts.vault_state.to_account_info(),
-        };
+        ctx.accounts.registry.assets.push(AssetEntry {
+            mint: asset,
+            exchange_rate: info.key(),
+            decimals,
+            status: AssetStatus::Active,
+        });
 
-        let seeds: &[&[u8]] = &[VAULT_STATE_SEED.as_ref(), &[ctx.accounts.vault_state.bump]];
-        let seeds = &[seeds][..];
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            cpi_accounts,
-            seeds,
-        );
-        token::mint_to(cpi_ctx, amt)?;
+        emit!(AssetRegisteredEvent {
+            asset,
+            exchange_rate: info.key(),
+            decimals,
+            salt: LEGACY_SALT,
+        });
 
-        emit!(DepositEvent{
-            who: ctx.accounts.minter.key(),
-            token_mint: ctx.accounts.collateral_token_mint.key(),
-            amt: collat,
+        Ok(())
+    }
+
+    /// Grows legacy permissions of the migrated legacy vault to the current layout. The
+    /// legacy layout is a prefix of the current one, so the whitelist flags carry over
+    pub fn migrate_permissions(ctx: Context<MigratePermissions>, user: Pubkey) -> Result<()> {
+        if ctx.accounts.admin.key() != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        let info = ctx.accounts.user_permissions.to_account_info();
+        check_legacy::<Permissions>(&info, LEGACY_PERMISSIONS_SPACE)?;
+        resize_account(
+            &info,
+            Permissions::SPACE,
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        emit!(PermissionsMigratedEvent {
+            user,
+            migrated_by: ctx.accounts.admin.key(),
         });
 
         Ok(())
     }
 
-    pub fn redeem(ctx: Context<Redeem>, amt: u64) -> Result<()> {
-        let rate = ctx.accounts.exchange_rate.redeem_rate as u128;
-        let decimals = ctx.accounts.collateral_token_mint.decimals;
-        let collat_raw: u64 = (amt as u128 * rate / DECIMALS_SCALAR).try_into().unwrap();
-        let collat = if decimals < 9 {
-            collat_raw / 10_u64.pow(9 - decimals as u32)
-        } else {
-            collat_raw
-        };
+    pub fn pause(ctx: Context<Pause>, salt: [u8; 8], flags: u8) -> Result<()> {
+        let caller = ctx.accounts.caller.key();
+        let vault_state = &mut ctx.accounts.vault_state;
 
-        if rate == 0 {
-            return Err(MintError::AssetNotSupported.into());
+        if caller != vault_state.admin && !has_role(&ctx.accounts.caller_role, ROLE_GUARDIAN) {
+            return Err(MintError::NotAdminOrGuardian.into());
         }
 
-        if !ctx.accounts.user_permissions.can_redeem {
-            return Err(MintError::NotAnApprovedRedeemer.into());
+        if flags == 0 || flags & !PAUSE_ALL != 0 {
+            return Err(MintError::InvalidPauseFlags.into());
         }
 
-        // Transfer collateral to the caller
-        let transfer_instruction = Transfer {
-            from: ctx.accounts.program_collateral.to_account_info(),
-            to: ctx.accounts.caller_collateral.to_account_info(),
-            authority: ctx.accounts.vault_state.to_account_info(),
-        };
+        vault_state.paused |= flags;
 
-        let seeds: &[&[u8]] = &[VAULT_STATE_SEED, &[ctx.bumps.vault_state]];
-        let seeds = &[seeds][..];
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_instruction,
-            seeds,
-        );
-        token::transfer(cpi_ctx, collat)?;
+        emit!(PausedEvent {
+            asset: None,
+            flags,
+            paused_by: caller,
+            salt,
+        });
 
-        // Burn staked tokens that caller redeemed
-        let cpi_accounts = Burn {
-            mint: ctx.accounts.vault_token_mint.to_account_info(),
-            from: ctx.accounts.caller_vault_token.to_account_info(),
-            authority: ctx.accounts.redeemer.to_account_info(),
-        };
+        Ok(())
+    }
 
-        let cpi_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            cpi_accounts,
-        );
-        token::burn(cpi_ctx, amt)?;
+    pub fn unpause(ctx: Context<Pause>, salt: [u8; 8], flags: u8) -> Result<()> {
+        let vault_state = &mut ctx.accounts.vault_state;
 
-        emit!(RedeemEvent{
-            who: ctx.accounts.redeemer.key(),
-            token_mint: ctx.accounts.collateral_token_mint.key(),
-            amt: amt,
+        if ctx.accounts.caller.key() != vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        if flags == 0 || flags & !PAUSE_ALL != 0 {
+            return Err(MintError::InvalidPauseFlags.into());
+        }
+
+        vault_state.paused &= !flags;
+
+        emit!(UnpausedEvent {
+            asset: None,
+            flags,
+            unpaused_by: ctx.accounts.caller.key(),
+            salt,
         });
 
         Ok(())
     }
 
-    pub fn withdraw(ctx: Context<Withdraw>, amt: u64) -> Result<()> {
-        if !ctx.accounts.vault_state.asset_managers.contains(&ctx.accounts.caller.key()) {
-            return Err(MintError::NotManager.into());
+    pub fn pause_asset(ctx: Context<PauseAsset>, salt: [u8; 8], asset: Pubkey, flags: u8) -> Result<()> {
+        let caller = ctx.accounts.caller.key();
+        let vault_state = &ctx.accounts.vault_state;
+
+        if caller != vault_state.admin && !has_role(&ctx.accounts.caller_role, ROLE_GUARDIAN) {
+            return Err(MintError::NotAdminOrGuardian.into());
         }
 
-        let destination = &ctx.accounts.destination.key();
-        if !ctx.accounts.vault_state.withdraw_addresses.contains(destination) {
-            return Err(MintError::NotWithdrawer.into());
+        if flags == 0 || flags & !PAUSE_ALL != 0 {
+            return Err(MintError::InvalidPauseFlags.into());
         }
 
-        // Transfer collateral
-        let transfer_instruction = Transfer {
-            from: ctx.accounts.program_collat.to_account_info(),
-            to: ctx.accounts.destination.to_account_info(),
-            authority: ctx.accounts.vault_state.to_account_info(),
-        };
+        ctx.accounts.exchange_rate.paused |= flags;
 
-        let seeds: &[&[u8]] = &[VAULT_STATE_SEED, &[ctx.accounts.vault_state.bump]];
-        let seeds = &[seeds][..];
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_instruction,
-            seeds,
-        );
+        emit!(PausedEvent {
+            asset: Some(asset),
+            flags,
+            paused_by: caller,
+            salt,
+        });
 
-        token::transfer(cpi_ctx, amt)?;
+        Ok(())
+    }
 
-        emit!(WithdrawEvent {
-            who: *destination,
-            token_mint: ctx.accounts.collat_mint.key(),
-            amt: amt,
+    pub fn unpause_asset(ctx: Context<PauseAsset>, salt: [u8; 8], asset: Pubkey, flags: u8) -> Result<()> {
+        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        if flags == 0 || flags & !PAUSE_ALL != 0 {
+            return Err(MintError::InvalidPauseFlags.into());
+        }
+
+        ctx.accounts.exchange_rate.paused &= !flags;
+
+        emit!(UnpausedEvent {
+            asset: Some(asset),
+            flags,
+            unpaused_by: ctx.accounts.caller.key(),
+            salt,
         });
 
         Ok(())
     }
 
-    pub fn whitelist_minter(ctx: Context<UserPermissions>, user: Pubkey) -> Result<()> {
-        if !ctx.accounts.vault_state.role_managers.contains(&ctx.accounts.caller.key()) {
-            return Err(MintError::NotManager.into());
+    pub fn queue_operation(ctx: Context<QueueOperation>, salt: [u8; 8], action: TimelockAction) -> Result<()> {
+        let vault_state = &mut ctx.accounts.vault_state;
+
+        if ctx.accounts.caller.key() != vault_state.admin {
+            return Err(MintError::NotAdmin.into());
         }
 
-        ctx.accounts.user_permissions.key = user;
-        ctx.accounts.user_permissions.can_mint = true;
+        if salt == LEGACY_SALT && matches!(action, TimelockAction::Seize { .. }) {
+            return Err(MintError::UnsupportedByLegacyVault.into());
+        }
 
-        emit!(NewMinterEvent{
-            new_minter: user,
-            added_by: ctx.accounts.caller.key(),
+        let eta = Clock::get()?.unix_timestamp + vault_state.timelock_delay as i64;
+        let operation = &mut ctx.accounts.operation;
+        operation.id = vault_state.operation_count;
+        operation.action = action.clone();
+        operation.eta = eta;
+        operation.proposer = ctx.accounts.caller.key();
+        operation.bump = ctx.bumps.operation;
+        vault_state.operation_count += 1;
+
+        emit!(OperationQueuedEvent {
+            id: operation.id,
+            action,
+            eta,
+            proposer: operation.proposer,
+            salt,
         });
 
         Ok(())
     }
 
-    pub fn remove_minter(ctx: Context<UserPermissions>, user: Pubkey) -> Result<()> {
-        if !ctx.accounts.vault_state.role_managers.contains(&ctx.accounts.caller.key()) {
-            return Err(MintError::NotManager.into());
-        }
+    pub fn cancel_operation(ctx: Context<CancelOperation>, salt: [u8; 8]) -> Result<()> {
+        let caller = ctx.accounts.caller.key();
+        let vault_state = &ctx.accounts.vault_state;
 
-        ctx.accounts.user_permissions.can_mint = false;
+        if caller != vault_state.admin && !has_role(&ctx.accounts.caller_role, ROLE_GUARDIAN) {
+            return Err(MintError::NotAdminOrGuardian.into());
+        }
 
-        emit!(MinterRemovedEvent{
-            removed: user,
-            removed_by: ctx.accounts.caller.key(),
+        emit!(OperationCancelledEvent {
+            id: ctx.accounts.operation.id,
+            cancelled_by: caller,
+            salt,
         });
 
         Ok(())
     }
 
-    pub fn whitelist_redeemer(ctx: Context<UserPermissions>, user: Pubkey) -> Result<()> {
-        if !ctx.accounts.vault_state.role_managers.contains(&ctx.accounts.caller.key()) {
-            return Err(MintError::NotManager.into());
+    pub fn set_timelock_delay(ctx: Context<SetTimelockDelay>, salt: [u8; 8], delay: u32) -> Result<()> {
+        ctx.accounts.operation.check_ready(
+            &TimelockAction::SetTimelockDelay { delay },
+            &ctx.accounts.vault_state,
+        )?;
+
+        if !(MIN_TIMELOCK_DELAY..=MAX_TIMELOCK_DELAY).contains(&delay) {
+            return Err(MintError::InvalidTimelockDelay.into());
         }
 
-        ctx.accounts.user_permissions.key = user;
-        ctx.accounts.user_permissions.can_redeem = true;
+        ctx.accounts.vault_state.timelock_delay = delay;
 
-        emit!(NewRedeemerEvent{
-            new_redeemer: user,
-            added_by: ctx.accounts.caller.key(),
+        emit!(TimelockDelayUpdatedEvent {
+            delay,
+            proposed_by: ctx.accounts.operation.proposer,
+            salt,
+        });
+
+        emit!(OperationExecutedEvent {
+            id: ctx.accounts.operation.id,
+            executed_by: ctx.accounts.caller.key(),
+            salt,
         });
 
         Ok(())
     }
 
-    pub fn remove_redeemer(ctx: Context<UserPermissions>, user: Pubkey) -> Result<()> {
-        if !ctx.accounts.vault_state.role_managers.contains(&ctx.accounts.caller.key()) {
-            return Err(MintError::NotManager.into());
-        }
+    // The deposit rate is (stable units / asset units) [how many stable coins a depositer will get per asset coin]
+    // The redeem rate is (asset units / stable units) [how many asset coins a depositer will get per stable coin]
+    pub fn update_asset(
+        ctx: Context<UpdateAsset>,
+        salt: [u8; 8],
+        asset: Pubkey,
+        deposit_rate: u64,
+        redeem_rate: u64,
+    ) -> Result<()> {
+        ctx.accounts.operation.check_ready(
+            &TimelockAction::UpdateAsset { asset, deposit_rate, redeem_rate },
+            &ctx.accounts.vault_state,
+        )?;
+
+        // A retired asset has its exchange rate closed, so check the registry
+        if ctx.accounts.registry.assets.iter().any(|entry| entry.mint == asset && entry.status == AssetStatus::Retired) {
+            return Err(MintError::AssetRetired.into());
+        }
+
+        if !ctx.accounts.registry.contains(&asset) {
+            let decimals = ctx.accounts.collateral_token_mint.decimals;
+            if decimals > MAX_COLLATERAL_DECIMALS {
+                return Err(MintError::UnsupportedDecimals.into());
+            }
+            ctx.accounts.exchange_rate.decimals = decimals;
+            ctx.accounts.exchange_rate.max_rate_change_bps = DEFAULT_MAX_RATE_CHANGE_BPS;
+        }
+
+        ctx.accounts.exchange_rate.asset = asset;
+        ctx.accounts.exchange_rate.apply_rates(deposit_rate, redeem_rate, Clock::get()?.unix_timestamp)?;
+
+        if !ctx.accounts.registry.contains(&asset) {
+            let decimals = ctx.accounts.exchange_rate.decimals;
+            ctx.accounts.registry.assets.push(AssetEntry {
+                mint: asset,
+                exchange_rate: ctx.accounts.exchange_rate.key(),
+                decimals,
+                status: AssetStatus::Active,
+            });
+
+            emit!(AssetRegisteredEvent {
+                asset,
+                exchange_rate: ctx.accounts.exchange_rate.key(),
+                decimals,
+                salt,
+            });
+        }
+
+        emit!(AssetModifiedEvent{
+            who: ctx.accounts.operation.proposer,
+            asset,
+            deposit_rate,
+            redeem_rate,
+            salt,
+        });
+
+        emit!(OperationExecutedEvent {
+            id: ctx.accounts.operation.id,
+            executed_by: ctx.accounts.authority.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Restricts a listed asset to deposits or redemptions only, or lifts the restriction
+    pub fn set_asset_status(ctx: Context<SetAssetStatus>, _salt: [u8; 8], asset: Pubkey, status: AssetStatus) -> Result<()> {
+        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        if status == AssetStatus::Retired {
+            return Err(MintError::InvalidAssetStatus.into());
+        }
+
+        ctx.accounts.set_status(asset, status)
+    }
+
+    /// Retires a redeem-only asset whose collateral has been fully paid out, closing its
+    /// exchange rate, collateral account and redeem reserve and returning the rent to the admin
+    pub fn retire_asset(ctx: Context<RetireAsset>, salt: [u8; 8], asset: Pubkey) -> Result<()> {
+        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        let exchange_rate = &ctx.accounts.exchange_rate;
+        if exchange_rate.status != AssetStatus::RedeemOnly {
+            return Err(MintError::AssetNotRedeemOnly.into());
+        }
+
+        if exchange_rate.next_fulfil != exchange_rate.next_ticket || exchange_rate.reserved != 0 {
+            return Err(MintError::RedemptionsOutstanding.into());
+        }
+
+        if ctx.accounts.program_collateral.amount != 0 || exchange_rate.held_externally != 0 {
+            return Err(MintError::CollateralRemaining.into());
+        }
+
+        let reserve_amount = ctx.accounts.redeem_reserve_amount()?;
+        if reserve_amount.is_some_and(|amount| amount != 0) {
+            return Err(MintError::CollateralRemaining.into());
+        }
+
+        let entry = ctx
+            .accounts
+            .registry
+            .assets
+            .iter_mut()
+            .find(|entry| entry.mint == asset)
+            .ok_or(MintError::AssetNotSupported)?;
+        entry.status = AssetStatus::Retired;
+
+        ctx.accounts.close_program_collateral()?;
+        if reserve_amount.is_some() {
+            ctx.accounts.close_redeem_reserve()?;
+        }
+
+        emit!(AssetStatusUpdatedEvent {
+            asset,
+            status: AssetStatus::Retired,
+            updated_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        emit!(AssetRetiredEvent {
+            asset,
+            exchange_rate: ctx.accounts.exchange_rate.key(),
+            program_collateral: ctx.accounts.program_collateral.key(),
+            rent_receiver: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_limits(
+        ctx: Context<SetLimits>,
+        salt: [u8; 8],
+        window: u64,
+        max_mint: u64,
+        max_redeem: u64,
+    ) -> Result<()> {
+        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        if window == 0 {
+            return Err(MintError::InvalidLimitWindow.into());
+        }
+
+        let vault_state = &mut ctx.accounts.vault_state;
+        vault_state.limit_window = window;
+        vault_state.mint_limit.max = max_mint;
+        vault_state.redeem_limit.max = max_redeem;
+
+        emit!(LimitsUpdatedEvent {
+            who: ctx.accounts.caller.key(),
+            window,
+            max_mint,
+            max_redeem,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_asset_limits(
+        ctx: Context<SetAssetLimits>,
+        salt: [u8; 8],
+        asset: Pubkey,
+        max_mint: u64,
+        max_redeem: u64,
+    ) -> Result<()> {
+        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        ctx.accounts.exchange_rate.mint_limit.max = max_mint;
+        ctx.accounts.exchange_rate.redeem_limit.max = max_redeem;
+
+        emit!(AssetLimitsUpdatedEvent {
+            who: ctx.accounts.caller.key(),
+            asset,
+            max_mint,
+            max_redeem,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Bounds how far and how often the rates of an asset can move
+    pub fn set_rate_limits(
+        ctx: Context<SetRateLimits>,
+        salt: [u8; 8],
+        asset: Pubkey,
+        max_change_bps: u16,
+        min_interval: u32,
+    ) -> Result<()> {
+        ctx.accounts.operation.check_ready(
+            &TimelockAction::SetRateLimits { asset, max_change_bps, min_interval },
+            &ctx.accounts.vault_state,
+        )?;
+
+        ctx.accounts.exchange_rate.max_rate_change_bps = max_change_bps;
+        ctx.accounts.exchange_rate.min_rate_interval = min_interval;
+
+        emit!(RateLimitsUpdatedEvent {
+            who: ctx.accounts.operation.proposer,
+            asset,
+            max_change_bps,
+            min_interval,
+            salt,
+        });
+
+        emit!(OperationExecutedEvent {
+            id: ctx.accounts.operation.id,
+            executed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Narrows the rate limits of an asset without the timelock, neither the allowed
+    /// change nor the update frequency can grow
+    pub fn tighten_rate_limits(
+        ctx: Context<SetAssetLimits>,
+        salt: [u8; 8],
+        asset: Pubkey,
+        max_change_bps: u16,
+        min_interval: u32,
+    ) -> Result<()> {
+        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        let exchange_rate = &mut ctx.accounts.exchange_rate;
+        if !cap_tightened(max_change_bps as u64, exchange_rate.max_rate_change_bps as u64)
+            || min_interval < exchange_rate.min_rate_interval
+        {
+            return Err(MintError::LooseningTimelocked.into());
+        }
+
+        exchange_rate.max_rate_change_bps = max_change_bps;
+        exchange_rate.min_rate_interval = min_interval;
+
+        emit!(RateLimitsUpdatedEvent {
+            who: ctx.accounts.caller.key(),
+            asset,
+            max_change_bps,
+            min_interval,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_oracle(ctx: Context<SetOracle>, salt: [u8; 8], asset: Pubkey, config: OracleConfig) -> Result<()> {
+        ctx.accounts.operation.check_ready(
+            &TimelockAction::SetOracle { asset, config },
+            &ctx.accounts.vault_state,
+        )?;
+
+        if config.price_decimals > MAX_PRICE_DECIMALS || config.max_deviation_bps as u128 > BPS_SCALAR {
+            return Err(MintError::InvalidOracleConfig.into());
+        }
+
+        // Zero reads as uncapped on every other limit, so it is not accepted as a tolerance
+        if config.feed != Pubkey::default() && (config.max_age == 0 || config.max_deviation_bps == 0) {
+            return Err(MintError::InvalidOracleConfig.into());
+        }
+
+        ctx.accounts.exchange_rate.oracle = config;
+
+        emit!(OracleUpdatedEvent {
+            who: ctx.accounts.operation.proposer,
+            asset,
+            feed: config.feed,
+            max_age: config.max_age,
+            max_deviation_bps: config.max_deviation_bps,
+            salt,
+        });
+
+        emit!(OperationExecutedEvent {
+            id: ctx.accounts.operation.id,
+            executed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_treasury(ctx: Context<Treasury>, salt: [u8; 8], treasury: Pubkey) -> Result<()> {
+        ctx.accounts.operation.check_ready(
+            &TimelockAction::SetTreasury { treasury },
+            &ctx.accounts.vault_state,
+        )?;
+
+        ctx.accounts.vault_state.treasury = treasury;
+
+        emit!(TreasuryUpdatedEvent {
+            treasury,
+            updated_by: ctx.accounts.operation.proposer,
+            salt,
+        });
+
+        emit!(OperationExecutedEvent {
+            id: ctx.accounts.operation.id,
+            executed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Changes the name, symbol and URI of the vault token
+    pub fn update_metadata(ctx: Context<UpdateMetadata>, salt: [u8; 8], metadata: MetadataParams) -> Result<()> {
+        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        if salt == LEGACY_SALT {
+            return Err(MintError::UnsupportedByLegacyVault.into());
+        }
+
+        emit!(MetadataUpdatedEvent {
+            name: metadata.name.clone(),
+            symbol: metadata.symbol.clone(),
+            uri: metadata.uri.clone(),
+            updated_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        let seeds: &[&[u8]] = &ctx.accounts.vault_state.signer_seeds();
+        let signer = &[seeds][..];
+        let metadata_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                update_authority: ctx.accounts.vault_state.to_account_info(),
+            },
+            signer,
+        );
+
+        update_metadata_accounts_v2(metadata_ctx, None, Some(metadata.into_data()), None, None)
+    }
+
+    pub fn set_fees(
+        ctx: Context<SetFees>,
+        salt: [u8; 8],
+        asset: Pubkey,
+        mint_fee_bps: u16,
+        redeem_fee_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.operation.check_ready(
+            &TimelockAction::SetFees { asset, mint_fee_bps, redeem_fee_bps },
+            &ctx.accounts.vault_state,
+        )?;
+
+        if mint_fee_bps > MAX_FEE_BPS || redeem_fee_bps > MAX_FEE_BPS {
+            return Err(MintError::InvalidFee.into());
+        }
+
+        ctx.accounts.exchange_rate.mint_fee_bps = mint_fee_bps;
+        ctx.accounts.exchange_rate.redeem_fee_bps = redeem_fee_bps;
+
+        emit!(FeesUpdatedEvent {
+            asset,
+            mint_fee_bps,
+            redeem_fee_bps,
+            proposed_by: ctx.accounts.operation.proposer,
+            salt,
+        });
+
+        emit!(OperationExecutedEvent {
+            id: ctx.accounts.operation.id,
+            executed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_quote_signer(ctx: Context<SetQuoteSigner>, salt: [u8; 8], signer: Pubkey) -> Result<()> {
+        ctx.accounts.operation.check_ready(
+            &TimelockAction::SetQuoteSigner { signer },
+            &ctx.accounts.vault_state,
+        )?;
+
+        let previous = ctx.accounts.vault_state.quote_signer;
+        ctx.accounts.vault_state.quote_signer = signer;
+
+        emit!(QuoteSignerUpdatedEvent {
+            previous,
+            signer,
+            proposed_by: ctx.accounts.operation.proposer,
+            salt,
+        });
+
+        emit!(OperationExecutedEvent {
+            id: ctx.accounts.operation.id,
+            executed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn freeze_holder(ctx: Context<FreezeHolder>, salt: [u8; 8]) -> Result<()> {
+        let caller = ctx.accounts.caller.key();
+
+        if caller != ctx.accounts.vault_state.admin && !has_role(&ctx.accounts.caller_role, ROLE_GUARDIAN) {
+            return Err(MintError::NotAdminOrGuardian.into());
+        }
+
+        if salt == LEGACY_SALT {
+            return Err(MintError::UnsupportedByLegacyVault.into());
+        }
+
+        if ctx.accounts.holder_vault_token.is_frozen() {
+            return Err(MintError::AccountFrozen.into());
+        }
+
+        ctx.accounts.freeze()?;
+
+        emit!(HolderFrozenEvent {
+            account: ctx.accounts.holder_vault_token.key(),
+            owner: ctx.accounts.holder_vault_token.owner,
+            frozen_by: caller,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn thaw_holder(ctx: Context<FreezeHolder>, salt: [u8; 8]) -> Result<()> {
+        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        if salt == LEGACY_SALT {
+            return Err(MintError::UnsupportedByLegacyVault.into());
+        }
+
+        if !ctx.accounts.holder_vault_token.is_frozen() {
+            return Err(MintError::AccountNotFrozen.into());
+        }
+
+        ctx.accounts.thaw()?;
+
+        emit!(HolderThawedEvent {
+            account: ctx.accounts.holder_vault_token.key(),
+            owner: ctx.accounts.holder_vault_token.owner,
+            thawed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn seize(
+        ctx: Context<Seize>,
+        salt: [u8; 8],
+        account: Pubkey,
+        amount: u64,
+        recovery: Option<Pubkey>,
+    ) -> Result<()> {
+        if salt == LEGACY_SALT {
+            return Err(MintError::UnsupportedByLegacyVault.into());
+        }
+
+        ctx.accounts.operation.check_ready(
+            &TimelockAction::Seize { account, amount, recovery },
+            &ctx.accounts.vault_state,
+        )?;
+
+        if ctx.accounts.recovery_vault_token.as_ref().map(|r| r.key()) != recovery {
+            return Err(MintError::InvalidRecoveryAccount.into());
+        }
+
+        // Only balances an admin or guardian has already frozen can be seized
+        if !ctx.accounts.holder_vault_token.is_frozen() {
+            return Err(MintError::AccountNotFrozen.into());
+        }
+
+        ctx.accounts.seize(amount)?;
+
+        emit!(SeizedEvent {
+            account,
+            owner: ctx.accounts.holder_vault_token.owner,
+            amount,
+            recovery,
+            proposed_by: ctx.accounts.operation.proposer,
+            salt,
+        });
+
+        emit!(OperationExecutedEvent {
+            id: ctx.accounts.operation.id,
+            executed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit(
+        ctx: Context<Deposit>,
+        salt: [u8; 8],
+        collat: u64,
+        min_amount_out: u64,
+        expiry_slot: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts.check_caller_vault_token()?;
+
+        let (received, fee) = ctx.accounts.process_deposit(collat, min_amount_out, expiry_slot)?;
+
+        emit!(DepositEvent{
+            who: ctx.accounts.minter.key(),
+            token_mint: ctx.accounts.collateral_token_mint.key(),
+            amt: received,
+            fee,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Deposits collateral of the minter and mints the vault tokens into an account
+    /// owned by someone else
+    pub fn deposit_for(
+        ctx: Context<Deposit>,
+        salt: [u8; 8],
+        collat: u64,
+        min_amount_out: u64,
+        expiry_slot: Option<u64>,
+    ) -> Result<()> {
+        let (received, fee) = ctx.accounts.process_deposit(collat, min_amount_out, expiry_slot)?;
+
+        emit!(DepositEvent{
+            who: ctx.accounts.minter.key(),
+            token_mint: ctx.accounts.collateral_token_mint.key(),
+            amt: received,
+            fee,
+            salt,
+        });
+
+        emit!(DepositForEvent {
+            payer: ctx.accounts.minter.key(),
+            receiver: ctx.accounts.caller_vault_token.owner,
+            token_account: ctx.accounts.caller_vault_token.key(),
+            token_mint: ctx.accounts.collateral_token_mint.key(),
+            amt: received,
+            fee,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Deposits at a price signed by the quote signer instead of the exchange rate.
+    /// The quoted amounts are final, no mint fee is charged on top.
+    pub fn deposit_quoted(ctx: Context<Deposit>, salt: [u8; 8], quote: Quote) -> Result<()> {
+        if (ctx.accounts.vault_state.paused | ctx.accounts.exchange_rate.paused) & PAUSE_MINT != 0 {
+            return Err(MintError::Paused.into());
+        }
+
+        if !ctx.accounts.exchange_rate.status.can_mint() {
+            return Err(MintError::DepositsDisabled.into());
+        }
+
+        if !ctx.accounts.user_permissions.can_mint {
+            return Err(MintError::NotAnApprovedMinter.into());
+        }
+
+        ctx.accounts.check_caller_vault_token()?;
+
+        accept_quote(
+            &quote,
+            QuoteSide::Mint,
+            &ctx.accounts.minter.key(),
+            &ctx.accounts.collateral_token_mint.key(),
+            &ctx.accounts.vault_state,
+            &mut ctx.accounts.user_permissions,
+            ctx.accounts.instructions.as_deref(),
+        )?;
+
+        let received = ctx.accounts.transfer_collateral_to_vault(quote.amount_in)?;
+
+        // The quoted price assumes the vault receives the whole amount_in, a transfer
+        // fee on the collateral would otherwise mint against collateral never received
+        if received != quote.amount_in {
+            return Err(MintError::QuoteAmountNotReceived.into());
+        }
+
+        ctx.accounts.consume_mint_limits(quote.amount_out)?;
+
+        ctx.accounts.mint_vault_tokens(ctx.accounts.caller_vault_token.to_account_info(), quote.amount_out)?;
+
+        emit!(DepositEvent{
+            who: ctx.accounts.minter.key(),
+            token_mint: ctx.accounts.collateral_token_mint.key(),
+            amt: received,
+            fee: 0,
+            salt,
+        });
+
+        emit!(QuoteFilledEvent {
+            user: quote.user,
+            asset: quote.asset,
+            side: quote.side,
+            amount_in: quote.amount_in,
+            amount_out: quote.amount_out,
+            nonce: quote.nonce,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn redeem(
+        ctx: Context<Redeem>,
+        salt: [u8; 8],
+        amt: u64,
+        min_amount_out: u64,
+        expiry_slot: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts.check_caller_collateral()?;
+
+        let fee = ctx.accounts.process_redeem(amt, min_amount_out, expiry_slot)?;
+
+        emit!(RedeemEvent{
+            who: ctx.accounts.redeemer.key(),
+            token_mint: ctx.accounts.collateral_token_mint.key(),
+            amt,
+            fee,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Redeems vault tokens of the redeemer and sends the collateral to an account
+    /// owned by someone else
+    pub fn redeem_to(
+        ctx: Context<Redeem>,
+        salt: [u8; 8],
+        amt: u64,
+        min_amount_out: u64,
+        expiry_slot: Option<u64>,
+    ) -> Result<()> {
+        let fee = ctx.accounts.process_redeem(amt, min_amount_out, expiry_slot)?;
+
+        emit!(RedeemEvent{
+            who: ctx.accounts.redeemer.key(),
+            token_mint: ctx.accounts.collateral_token_mint.key(),
+            amt,
+            fee,
+            salt,
+        });
+
+        emit!(RedeemToEvent {
+            payer: ctx.accounts.redeemer.key(),
+            receiver: ctx.accounts.caller_collateral.owner,
+            token_account: ctx.accounts.caller_collateral.key(),
+            token_mint: ctx.accounts.collateral_token_mint.key(),
+            amt,
+            fee,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Redeems at a price signed by the quote signer instead of the exchange rate.
+    /// The quoted amounts are final, no redeem fee is charged on top.
+    pub fn redeem_quoted(ctx: Context<Redeem>, salt: [u8; 8], quote: Quote) -> Result<()> {
+        if (ctx.accounts.vault_state.paused | ctx.accounts.exchange_rate.paused) & PAUSE_REDEEM != 0 {
+            return Err(MintError::Paused.into());
+        }
+
+        if !ctx.accounts.exchange_rate.status.can_redeem() {
+            return Err(MintError::RedemptionsDisabled.into());
+        }
+
+        if !ctx.accounts.user_permissions.can_redeem {
+            return Err(MintError::NotAnApprovedRedeemer.into());
+        }
+
+        ctx.accounts.check_caller_collateral()?;
+
+        accept_quote(
+            &quote,
+            QuoteSide::Redeem,
+            &ctx.accounts.redeemer.key(),
+            &ctx.accounts.collateral_token_mint.key(),
+            &ctx.accounts.vault_state,
+            &mut ctx.accounts.user_permissions,
+            ctx.accounts.instructions.as_deref(),
+        )?;
+
+        ctx.accounts.consume_redeem_limits(quote.amount_in)?;
+
+        ctx.accounts.transfer_collateral_to_caller(quote.amount_out)?;
+
+        ctx.accounts.burn_vault_tokens(quote.amount_in)?;
+
+        emit!(RedeemEvent{
+            who: ctx.accounts.redeemer.key(),
+            token_mint: ctx.accounts.collateral_token_mint.key(),
+            amt: quote.amount_in,
+            fee: 0,
+            salt,
+        });
+
+        emit!(QuoteFilledEvent {
+            user: quote.user,
+            asset: quote.asset,
+            side: quote.side,
+            amount_in: quote.amount_in,
+            amount_out: quote.amount_out,
+            nonce: quote.nonce,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Escrows vault tokens in a ticket that asset managers fulfil in order once they
+    /// have moved the collateral back from custody. The collateral owed is fixed at the
+    /// current redeem rate.
+    pub fn request_redeem(
+        ctx: Context<RequestRedeem>,
+        salt: [u8; 8],
+        amt: u64,
+        min_amount_out: u64,
+        expiry_slot: Option<u64>,
+    ) -> Result<()> {
+        let (fee, collat) = quote_redeem(
+            &ctx.accounts.vault_state,
+            &ctx.accounts.exchange_rate,
+            &ctx.accounts.user_permissions,
+            ctx.accounts.oracle.as_deref(),
+            amt,
+            min_amount_out,
+            expiry_slot,
+        )?;
+
+        consume_redeem_limits(
+            &mut ctx.accounts.vault_state,
+            &mut ctx.accounts.exchange_rate,
+            &mut ctx.accounts.user_permissions,
+            amt,
+        )?;
+
+        // Move the fee to the treasury
+        if fee > 0 {
+            ctx.accounts.transfer_fee_to_treasury(fee)?;
+        }
+
+        ctx.accounts.escrow_vault_tokens(amt - fee)?;
+
+        let id = ctx.accounts.exchange_rate.next_ticket;
+        ctx.accounts.exchange_rate.next_ticket += 1;
+
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.id = id;
+        ticket.asset = ctx.accounts.collateral_token_mint.key();
+        ticket.owner = ctx.accounts.redeemer.key();
+        ticket.amount = amt - fee;
+        ticket.collateral = collat;
+        ticket.status = TicketStatus::Pending;
+        ticket.bump = ctx.bumps.ticket;
+
+        emit!(RedeemRequestedEvent {
+            id,
+            asset: ticket.asset,
+            owner: ticket.owner,
+            amount: ticket.amount,
+            collateral: collat,
+            fee,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Funds the oldest open ticket of an asset from the caller and burns its escrowed
+    /// vault tokens. Cancelled tickets are skipped and their rent returned to the owner.
+    /// The funds are taken to come back from custody, what arrives in the reserve is
+    /// taken off the collateral held externally.
+    pub fn fulfil_redeem(ctx: Context<FulfilRedeem>, salt: [u8; 8]) -> Result<()> {
+        if !ctx.accounts.caller_role.has(ROLE_ASSET_MANAGER) {
+            return Err(MintError::NotManager.into());
+        }
+
+        if ctx.accounts.ticket.id != ctx.accounts.exchange_rate.next_fulfil {
+            return Err(MintError::TicketOutOfOrder.into());
+        }
+        ctx.accounts.exchange_rate.next_fulfil += 1;
+
+        if ctx.accounts.ticket.status == TicketStatus::Cancelled {
+            return ctx.accounts.ticket.close(ctx.accounts.owner.to_account_info());
+        }
+
+        // Fund the ticket
+        let transfer_instruction = TransferChecked {
+            from: ctx.accounts.caller_collateral.to_account_info(),
+            mint: ctx.accounts.collateral_token_mint.to_account_info(),
+            to: ctx.accounts.redeem_reserve.to_account_info(),
+            authority: ctx.accounts.caller.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.collateral_token_program.to_account_info(),
+            transfer_instruction,
+        );
+        let balance_before = ctx.accounts.redeem_reserve.amount;
+        token_interface::transfer_checked(
+            cpi_ctx,
+            ctx.accounts.ticket.collateral,
+            ctx.accounts.collateral_token_mint.decimals,
+        )?;
+
+        // A transfer fee on the collateral leaves less in the reserve than was sent,
+        // the ticket can only claim what actually arrived
+        ctx.accounts.redeem_reserve.reload()?;
+        let received = ctx.accounts.redeem_reserve.amount - balance_before;
+
+        // Funding comes back from custody
+        let exchange_rate = &mut ctx.accounts.exchange_rate;
+        exchange_rate.held_externally = exchange_rate.held_externally.saturating_sub(received);
+        exchange_rate.reserved += received;
+        ctx.accounts.ticket.collateral = received;
+
+        // Burn the escrowed vault tokens
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.vault_token_mint.to_account_info(),
+            from: ctx.accounts.redeem_escrow.to_account_info(),
+            authority: ctx.accounts.vault_state.to_account_info(),
+        };
+
+        let seeds: &[&[u8]] = &ctx.accounts.vault_state.signer_seeds();
+        let seeds = &[seeds][..];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            seeds,
+        );
+        token_interface::burn(cpi_ctx, ctx.accounts.ticket.amount)?;
+
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.status = TicketStatus::Fulfilled;
+
+        emit!(RedeemFulfilledEvent {
+            id: ticket.id,
+            asset: ticket.asset,
+            owner: ticket.owner,
+            collateral: ticket.collateral,
+            fulfilled_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Pays out the collateral of a fulfilled ticket and closes it
+    pub fn claim_redeem(ctx: Context<ClaimRedeem>, salt: [u8; 8]) -> Result<()> {
+        if (ctx.accounts.vault_state.paused | ctx.accounts.exchange_rate.paused) & PAUSE_REDEEM != 0 {
+            return Err(MintError::Paused.into());
+        }
+
+        if ctx.accounts.ticket.status != TicketStatus::Fulfilled {
+            return Err(MintError::TicketNotFulfilled.into());
+        }
+
+        let balance_before = ctx.accounts.owner_collateral.amount;
+        let transfer_instruction = TransferChecked {
+            from: ctx.accounts.redeem_reserve.to_account_info(),
+            mint: ctx.accounts.collateral_token_mint.to_account_info(),
+            to: ctx.accounts.owner_collateral.to_account_info(),
+            authority: ctx.accounts.vault_state.to_account_info(),
+        };
+
+        let seeds: &[&[u8]] = &ctx.accounts.vault_state.signer_seeds();
+        let seeds = &[seeds][..];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.collateral_token_program.to_account_info(),
+            transfer_instruction,
+            seeds,
+        );
+        token_interface::transfer_checked(
+            cpi_ctx,
+            ctx.accounts.ticket.collateral,
+            ctx.accounts.collateral_token_mint.decimals,
+        )?;
+
+        ctx.accounts.owner_collateral.reload()?;
+        let received = ctx.accounts.owner_collateral.amount - balance_before;
+
+        let exchange_rate = &mut ctx.accounts.exchange_rate;
+        exchange_rate.total_redeemed = exchange_rate.total_redeemed.saturating_add(received);
+        exchange_rate.reserved -= ctx.accounts.ticket.collateral;
+
+        emit!(RedeemClaimedEvent {
+            id: ctx.accounts.ticket.id,
+            asset: ctx.accounts.ticket.asset,
+            owner: ctx.accounts.owner.key(),
+            collateral: ctx.accounts.ticket.collateral,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the escrowed vault tokens of a ticket that has not been fulfilled yet. The
+    /// redeem limits and quotas the request consumed are not refunded, the cancelled
+    /// amount keeps counting against them until their windows roll over.
+    pub fn cancel_redeem(ctx: Context<CancelRedeem>, salt: [u8; 8]) -> Result<()> {
+        if ctx.accounts.ticket.status != TicketStatus::Pending {
+            return Err(MintError::TicketNotPending.into());
+        }
+
+        let transfer_instruction = TransferChecked {
+            from: ctx.accounts.redeem_escrow.to_account_info(),
+            mint: ctx.accounts.vault_token_mint.to_account_info(),
+            to: ctx.accounts.owner_vault_token.to_account_info(),
+            authority: ctx.accounts.vault_state.to_account_info(),
+        };
+
+        let seeds: &[&[u8]] = &ctx.accounts.vault_state.signer_seeds();
+        let seeds = &[seeds][..];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_instruction,
+            seeds,
+        );
+        token_interface::transfer_checked(
+            cpi_ctx,
+            ctx.accounts.ticket.amount,
+            ctx.accounts.vault_token_mint.decimals,
+        )?;
+
+        // The ticket stays open until the queue reaches it so fulfilment stays in order
+        let ticket = &mut ctx.accounts.ticket;
+        ticket.status = TicketStatus::Cancelled;
+
+        emit!(RedeemCancelledEvent {
+            id: ticket.id,
+            asset: ticket.asset,
+            owner: ticket.owner,
+            amount: ticket.amount,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Returns collateral from a custodian to the vault
+    pub fn repay(ctx: Context<Repay>, salt: [u8; 8], amt: u64) -> Result<()> {
+        if !ctx.accounts.caller_role.has(ROLE_ASSET_MANAGER) {
+            return Err(MintError::NotManager.into());
+        }
+
+        let balance_before = ctx.accounts.program_collateral.amount;
+        let transfer_instruction = TransferChecked {
+            from: ctx.accounts.caller_collateral.to_account_info(),
+            mint: ctx.accounts.collateral_token_mint.to_account_info(),
+            to: ctx.accounts.program_collateral.to_account_info(),
+            authority: ctx.accounts.caller.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.collateral_token_program.to_account_info(),
+            transfer_instruction,
+        );
+        token_interface::transfer_checked(cpi_ctx, amt, ctx.accounts.collateral_token_mint.decimals)?;
+
+        ctx.accounts.program_collateral.reload()?;
+        let received = ctx.accounts.program_collateral.amount - balance_before;
+
+        let exchange_rate = &mut ctx.accounts.exchange_rate;
+        exchange_rate.held_externally = exchange_rate.held_externally.saturating_sub(received);
+
+        emit!(RepayEvent {
+            asset: ctx.accounts.collateral_token_mint.key(),
+            amt: received,
+            held_externally: exchange_rate.held_externally,
+            repaid_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Reports the value of the collateral backing the vault token supply. Takes an
+    /// (exchange rate, program collateral) pair in the remaining accounts for every asset
+    /// in the registry that is not retired. Collateral is valued at the deposit rate and
+    /// includes collateral held externally. The redeem reserve is owed to fulfilled tickets
+    /// and does not back the supply.
+    pub fn backing<'info>(ctx: Context<'_, '_, 'info, 'info, Backing<'info>>, _salt: [u8; 8]) -> Result<BackingReport> {
+        // is_multiple_of is newer than the rustc of the SBF toolchain
+        #[allow(clippy::manual_is_multiple_of)]
+        if ctx.remaining_accounts.len() % 2 != 0 {
+            return Err(MintError::InvalidBackingAccounts.into());
+        }
+
+        let mut assets: Vec<Pubkey> = Vec::new();
+        let mut backing: u128 = 0;
+        for accounts in ctx.remaining_accounts.chunks(2) {
+            let exchange_rate: Account<ExchangeRate> = Account::try_from(&accounts[0])?;
+            let program_collateral: InterfaceAccount<TokenAccount> = InterfaceAccount::try_from(&accounts[1])?;
+
+            // Both accounts must belong to this vault instance
+            let (exchange_rate_key, _) = Pubkey::find_program_address(
+                &[EXCHANGE_RATE_SEED, ctx.accounts.vault_state.scope(), exchange_rate.asset.as_ref()],
+                ctx.program_id,
+            );
+            let (collateral_key, _) = Pubkey::find_program_address(
+                &[TOKEN_ACCOUNT_SEED, ctx.accounts.vault_state.scope(), exchange_rate.asset.as_ref()],
+                ctx.program_id,
+            );
+            if assets.contains(&exchange_rate.asset)
+                || exchange_rate.key() != exchange_rate_key
+                || program_collateral.key() != collateral_key
+            {
+                return Err(MintError::InvalidBackingAccounts.into());
+            }
+            assets.push(exchange_rate.asset);
+
+            backing += exchange_rate.backing(program_collateral.amount)?;
+        }
+
+        // Every listed asset that is not retired has to be counted
+        let listed = ctx.accounts.registry.assets.iter().filter(|entry| entry.status != AssetStatus::Retired).count();
+        if assets.len() != listed {
+            return Err(MintError::InvalidBackingAccounts.into());
+        }
+
+        let supply = ctx.accounts.vault_token_mint.supply;
+        Ok(BackingReport {
+            backing: backing.try_into().unwrap_or(u64::MAX),
+            supply,
+            ratio_bps: backing_ratio_bps(backing, supply),
+        })
+    }
+
+    pub fn set_withdraw_policy(
+        ctx: Context<SetWithdrawPolicy>,
+        salt: [u8; 8],
+        destination: Pubkey,
+        allowed_mints: Vec<Pubkey>,
+        max_per_withdraw: u64,
+        daily_limit: u64,
+    ) -> Result<()> {
+        ctx.accounts.operation.check_ready(
+            &TimelockAction::SetWithdrawPolicy {
+                destination,
+                allowed_mints: allowed_mints.clone(),
+                max_per_withdraw,
+                daily_limit,
+            },
+            &ctx.accounts.vault_state,
+        )?;
+
+        ctx.accounts.policy.update(&allowed_mints, max_per_withdraw, daily_limit)?;
+
+        emit!(WithdrawPolicyUpdatedEvent {
+            destination,
+            allowed_mints,
+            max_per_withdraw,
+            daily_limit,
+            updated_by: ctx.accounts.operation.proposer,
+            salt,
+        });
+
+        emit!(OperationExecutedEvent {
+            id: ctx.accounts.operation.id,
+            executed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Narrows a withdraw policy without the timelock. Every allowed mint must already
+    /// be allowed and neither limit can grow, anything else goes through set_withdraw_policy.
+    pub fn tighten_withdraw_policy(
+        ctx: Context<TightenWithdrawPolicy>,
+        salt: [u8; 8],
+        destination: Pubkey,
+        allowed_mints: Vec<Pubkey>,
+        max_per_withdraw: u64,
+        daily_limit: u64,
+    ) -> Result<()> {
+        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        let policy = &mut ctx.accounts.policy;
+        if !policy.is_tightened_by(&allowed_mints, max_per_withdraw, daily_limit) {
+            return Err(MintError::LooseningTimelocked.into());
+        }
+
+        policy.update(&allowed_mints, max_per_withdraw, daily_limit)?;
+
+        emit!(WithdrawPolicyUpdatedEvent {
+            destination,
+            allowed_mints,
+            max_per_withdraw,
+            daily_limit,
+            updated_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_withdraw_threshold(ctx: Context<SetWithdrawThreshold>, salt: [u8; 8], threshold: u8) -> Result<()> {
+        ctx.accounts.operation.check_ready(
+            &TimelockAction::SetWithdrawThreshold { threshold },
+            &ctx.accounts.vault_state,
+        )?;
+
+        if threshold < MIN_WITHDRAW_THRESHOLD || threshold as usize > MAX_WITHDRAW_APPROVALS {
+            return Err(MintError::InvalidWithdrawThreshold.into());
+        }
+
+        ctx.accounts.vault_state.withdraw_threshold = threshold;
+
+        emit!(WithdrawThresholdUpdatedEvent {
+            threshold,
+            proposed_by: ctx.accounts.operation.proposer,
+            salt,
+        });
+
+        emit!(OperationExecutedEvent {
+            id: ctx.accounts.operation.id,
+            executed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn propose_withdraw(
+        ctx: Context<ProposeWithdraw>,
+        salt: [u8; 8],
+        asset: Pubkey,
+        amount: u64,
+        destination: Pubkey,
+        deadline: i64,
+    ) -> Result<()> {
+        let vault_state = &mut ctx.accounts.vault_state;
+        let caller = ctx.accounts.caller.key();
+
+        if !ctx.accounts.caller_role.has(ROLE_ASSET_MANAGER) {
+            return Err(MintError::NotManager.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        if deadline <= now || deadline > now + MAX_WITHDRAW_PROPOSAL_TTL {
+            return Err(MintError::InvalidDeadline.into());
+        }
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.id = vault_state.withdraw_proposal_count;
+        proposal.asset = asset;
+        proposal.amount = amount;
+        proposal.destination = destination;
+        proposal.proposer = caller;
+        proposal.approvals = vec![caller];
+        proposal.deadline = deadline;
+        proposal.bump = ctx.bumps.proposal;
+        vault_state.withdraw_proposal_count += 1;
+
+        emit!(WithdrawProposedEvent {
+            id: proposal.id,
+            proposer: caller,
+            asset,
+            amount,
+            destination,
+            deadline,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn approve_withdraw(ctx: Context<ApproveWithdraw>, salt: [u8; 8]) -> Result<()> {
+        let caller = ctx.accounts.caller.key();
+
+        if !ctx.accounts.caller_role.has(ROLE_ASSET_MANAGER) {
+            return Err(MintError::NotManager.into());
+        }
+
+        let proposal = &mut ctx.accounts.proposal;
+        if Clock::get()?.unix_timestamp > proposal.deadline {
+            return Err(MintError::ProposalExpired.into());
+        }
+
+        if proposal.approvals.contains(&caller) {
+            return Err(MintError::AlreadyApproved.into());
+        }
+
+        if proposal.approvals.len() >= MAX_WITHDRAW_APPROVALS {
+            return Err(MintError::MaxArrayLength.into());
+        }
+
+        proposal.approvals.push(caller);
+
+        emit!(WithdrawApprovedEvent {
+            id: proposal.id,
+            approver: caller,
+            approvals: proposal.approvals.len() as u8,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_withdraw(ctx: Context<CancelWithdraw>, salt: [u8; 8]) -> Result<()> {
+        let caller = ctx.accounts.caller.key();
+
+        if caller != ctx.accounts.proposal.proposer && caller != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotProposerOrAdmin.into());
+        }
+
+        emit!(WithdrawCancelledEvent {
+            id: ctx.accounts.proposal.id,
+            cancelled_by: caller,
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Executes an approved proposal. The role accounts of the approvers are passed in
+    /// the remaining accounts so that only approvals of current asset managers count.
+    pub fn withdraw<'info>(ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>, salt: [u8; 8]) -> Result<()> {
+        let vault_state = &ctx.accounts.vault_state;
+        if (vault_state.paused | ctx.accounts.exchange_rate.paused) & PAUSE_WITHDRAW != 0 {
+            return Err(MintError::Paused.into());
+        }
+
+        if !ctx.accounts.caller_role.has(ROLE_ASSET_MANAGER) {
+            return Err(MintError::NotManager.into());
+        }
+
+        let destination = &ctx.accounts.destination.key();
+
+        let proposal = &ctx.accounts.proposal;
+        if Clock::get()?.unix_timestamp > proposal.deadline {
+            return Err(MintError::ProposalExpired.into());
+        }
+
+        let approvals = count_manager_approvals(
+            &proposal.approvals,
+            &ctx.accounts.vault_state.key(),
+            ctx.program_id,
+            ctx.remaining_accounts,
+        )?;
+        if approvals < vault_state.withdraw_threshold as usize {
+            return Err(MintError::NotEnoughApprovals.into());
+        }
+
+        let amt = proposal.amount;
+
+        let policy = &mut ctx.accounts.policy;
+        if !policy.allowed_mints.contains(&proposal.asset) {
+            return Err(MintError::AssetNotAllowed.into());
+        }
+
+        if policy.max_per_withdraw != 0 && amt > policy.max_per_withdraw {
+            return Err(MintError::MaxWithdrawExceeded.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp as u64;
+        if !policy.daily_limit.consume(amt, now, WITHDRAW_POLICY_WINDOW) {
+            return Err(MintError::DailyWithdrawLimitExceeded.into());
+        }
+
+        // Transfer collateral
+        let transfer_instruction = TransferChecked {
+            from: ctx.accounts.program_collat.to_account_info(),
+            mint: ctx.accounts.collat_mint.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.vault_state.to_account_info(),
+        };
+
+        let seeds: &[&[u8]] = &ctx.accounts.vault_state.signer_seeds();
+        let seeds = &[seeds][..];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_instruction,
+            seeds,
+        );
+
+        token_interface::transfer_checked(cpi_ctx, amt, ctx.accounts.collat_mint.decimals)?;
+
+        let exchange_rate = &mut ctx.accounts.exchange_rate;
+        exchange_rate.held_externally = exchange_rate.held_externally.saturating_add(amt);
+
+        emit!(WithdrawEvent {
+            who: *destination,
+            token_mint: ctx.accounts.collat_mint.key(),
+            amt,
+            salt,
+        });
+
+        emit!(WithdrawExecutedEvent {
+            id: ctx.accounts.proposal.id,
+            executed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn whitelist_minter(ctx: Context<UserPermissions>, salt: [u8; 8], user: Pubkey) -> Result<()> {
+        if !ctx.accounts.caller_role.has(ROLE_ROLE_MANAGER) {
+            return Err(MintError::NotManager.into());
+        }
+
+        ctx.accounts.user_permissions.key = user;
+        ctx.accounts.user_permissions.can_mint = true;
+
+        emit!(NewMinterEvent{
+            new_minter: user,
+            added_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn remove_minter(ctx: Context<UserPermissions>, salt: [u8; 8], user: Pubkey) -> Result<()> {
+        if !ctx.accounts.caller_role.has(ROLE_ROLE_MANAGER) {
+            return Err(MintError::NotManager.into());
+        }
+
+        ctx.accounts.user_permissions.can_mint = false;
+
+        emit!(MinterRemovedEvent{
+            removed: user,
+            removed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn whitelist_redeemer(ctx: Context<UserPermissions>, salt: [u8; 8], user: Pubkey) -> Result<()> {
+        if !ctx.accounts.caller_role.has(ROLE_ROLE_MANAGER) {
+            return Err(MintError::NotManager.into());
+        }
+
+        ctx.accounts.user_permissions.key = user;
+        ctx.accounts.user_permissions.can_redeem = true;
+
+        emit!(NewRedeemerEvent{
+            new_redeemer: user,
+            added_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn remove_redeemer(ctx: Context<UserPermissions>, salt: [u8; 8], user: Pubkey) -> Result<()> {
+        if !ctx.accounts.caller_role.has(ROLE_ROLE_MANAGER) {
+            return Err(MintError::NotManager.into());
+        }
+
+        ctx.accounts.user_permissions.can_redeem = false;
+
+        emit!(RedeemerRemovedEvent{
+            removed: user,
+            removed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Closes the permissions of a user that can neither mint nor redeem. Permissions that
+    /// used a quote or count towards a lifetime cap are kept, closing them would let the
+    /// quotes be replayed and the caps start over.
+    pub fn close_permissions(ctx: Context<ClosePermissions>, salt: [u8; 8], user: Pubkey) -> Result<()> {
+        if !ctx.accounts.caller_role.has(ROLE_ROLE_MANAGER) {
+            return Err(MintError::NotManager.into());
+        }
+
+        if ctx.accounts.user_permissions.can_mint || ctx.accounts.user_permissions.can_redeem {
+            return Err(MintError::PermissionsActive.into());
+        }
+
+        if ctx.accounts.user_permissions.has_history() {
+            return Err(MintError::PermissionsHaveHistory.into());
+        }
+
+        emit!(PermissionsClosedEvent {
+            user,
+            rent_receiver: ctx.accounts.rent_receiver.key(),
+            closed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Removes a user as minter and redeemer and closes their permissions if they have
+    /// no quote nonce or lifetime totals to preserve
+    pub fn revoke_all(ctx: Context<RevokeAll>, salt: [u8; 8], user: Pubkey) -> Result<()> {
+        if !ctx.accounts.caller_role.has(ROLE_ROLE_MANAGER) {
+            return Err(MintError::NotManager.into());
+        }
+
+        let permissions = &mut ctx.accounts.user_permissions;
+        if permissions.can_mint {
+            permissions.can_mint = false;
+            emit!(MinterRemovedEvent{
+                removed: user,
+                removed_by: ctx.accounts.caller.key(),
+                salt,
+            });
+        }
+
+        if permissions.can_redeem {
+            permissions.can_redeem = false;
+            emit!(RedeemerRemovedEvent{
+                removed: user,
+                removed_by: ctx.accounts.caller.key(),
+                salt,
+            });
+        }
+
+        if permissions.has_history() {
+            return Ok(());
+        }
+
+        permissions.close(ctx.accounts.rent_receiver.to_account_info())?;
+
+        emit!(PermissionsClosedEvent {
+            user,
+            rent_receiver: ctx.accounts.rent_receiver.key(),
+            closed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_user_quotas(
+        ctx: Context<UserPermissions>,
+        salt: [u8; 8],
+        user: Pubkey,
+        window: u64,
+        max_mint: u64,
+        max_redeem: u64,
+        max_total_mint: u64,
+        max_total_redeem: u64,
+    ) -> Result<()> {
+        if !ctx.accounts.caller_role.has(ROLE_ROLE_MANAGER) {
+            return Err(MintError::NotManager.into());
+        }
+
+        let permissions = &mut ctx.accounts.user_permissions;
+        permissions.key = user;
+        permissions.quota_window = window;
+        permissions.mint_quota.max = max_mint;
+        permissions.redeem_quota.max = max_redeem;
+        permissions.max_total_mint = max_total_mint;
+        permissions.max_total_redeem = max_total_redeem;
+
+        emit!(UserQuotasUpdatedEvent {
+            user,
+            window,
+            max_mint,
+            max_redeem,
+            max_total_mint,
+            max_total_redeem,
+            updated_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn grant_role(ctx: Context<GrantRole>, salt: [u8; 8], holder: Pubkey, roles: u8) -> Result<()> {
+        ctx.accounts.operation.check_ready(
+            &TimelockAction::GrantRole { holder, roles },
+            &ctx.accounts.vault_state,
+        )?;
+
+        if roles == 0 || roles & !ROLE_ALL != 0 {
+            return Err(MintError::InvalidRoles.into());
+        }
+
+        let role = &mut ctx.accounts.role;
+        role.holder = holder;
+        role.roles |= roles;
+        role.bump = ctx.bumps.role;
+
+        emit!(RoleGrantedEvent {
+            holder,
+            granted: roles,
+            roles: role.roles,
+            granted_by: ctx.accounts.operation.proposer,
+            salt,
+        });
+
+        emit!(OperationExecutedEvent {
+            id: ctx.accounts.operation.id,
+            executed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn revoke_role(ctx: Context<RevokeRole>, salt: [u8; 8], holder: Pubkey, roles: u8) -> Result<()> {
+        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        if roles == 0 || roles & !ROLE_ALL != 0 {
+            return Err(MintError::InvalidRoles.into());
+        }
+
+        let role = &mut ctx.accounts.role;
+        role.roles &= !roles;
+
+        emit!(RoleRevokedEvent {
+            holder,
+            revoked: roles,
+            roles: role.roles,
+            revoked_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    /// Closes the role account of a holder without roles and returns its rent to the caller
+    pub fn close_role(ctx: Context<CloseRole>, salt: [u8; 8], holder: Pubkey) -> Result<()> {
+        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        if ctx.accounts.role.roles != 0 {
+            return Err(MintError::RoleNotEmpty.into());
+        }
+
+        emit!(RoleClosedEvent {
+            holder,
+            closed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn add_withdraw_address(ctx: Context<AddWithdrawAddress>, salt: [u8; 8], address: Pubkey) -> Result<()> {
+        ctx.accounts.operation.check_ready(
+            &TimelockAction::AddWithdrawAddress { address },
+            &ctx.accounts.vault_state,
+        )?;
+
+        // Nothing can be withdrawn until the admin sets the policy
+        let policy = &mut ctx.accounts.policy;
+        policy.destination = address;
+        policy.bump = ctx.bumps.policy;
+
+        emit!(WithdrawAddressAdded {
+            address,
+            added_by: ctx.accounts.operation.proposer,
+            salt,
+        });
+
+        emit!(OperationExecutedEvent {
+            id: ctx.accounts.operation.id,
+            executed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn remove_withdraw_address(ctx: Context<WithdrawAddresses>, salt: [u8; 8], address: Pubkey) -> Result<()> {
+        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+
+        emit!(WithdrawAddressRemoved{
+            address,
+            removed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, salt: [u8; 8], new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.operation.check_ready(
+            &TimelockAction::ProposeAdmin { new_admin },
+            &ctx.accounts.vault_state,
+        )?;
+
+        ctx.accounts.vault_state.pending_admin = new_admin;
+
+        emit!(AdminProposedEvent {
+            admin: ctx.accounts.operation.proposer,
+            pending_admin: new_admin,
+            salt,
+        });
+
+        emit!(OperationExecutedEvent {
+            id: ctx.accounts.operation.id,
+            executed_by: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<TransferAdmin>, salt: [u8; 8]) -> Result<()> {
+        let vault_state = &mut ctx.accounts.vault_state;
+
+        if vault_state.pending_admin == Pubkey::default() {
+            return Err(MintError::NoPendingAdmin.into());
+        }
+
+        if ctx.accounts.caller.key() != vault_state.pending_admin {
+            return Err(MintError::NotPendingAdmin.into());
+        }
+
+        let old_admin = vault_state.admin;
+        vault_state.admin = vault_state.pending_admin;
+        vault_state.pending_admin = Pubkey::default();
+
+        emit!(AdminTransferEvent {
+            old_admin,
+            new_admin: ctx.accounts.caller.key(),
+            salt,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_admin_transfer(ctx: Context<TransferAdmin>, salt: [u8; 8]) -> Result<()> {
+        let vault_state = &mut ctx.accounts.vault_state;
+
+        if ctx.accounts.caller.key() != vault_state.admin {
+            return Err(MintError::NotAdmin.into());
+        }
+
+        if vault_state.pending_admin == Pubkey::default() {
+            return Err(MintError::NoPendingAdmin.into());
+        }
+
+        let cancelled = vault_state.pending_admin;
+        vault_state.pending_admin = Pubkey::default();
+
+        emit!(AdminTransferCancelledEvent {
+            admin: ctx.accounts.caller.key(),
+            cancelled_admin: cancelled,
+            salt,
+        });
+
+        Ok(())
+    }
+}
+
+impl AssetStatus {
+    pub fn can_mint(&self) -> bool {
+        matches!(self, AssetStatus::Active | AssetStatus::DepositOnly)
+    }
+
+    pub fn can_redeem(&self) -> bool {
+        matches!(self, AssetStatus::Active | AssetStatus::RedeemOnly)
+    }
+}
+
+impl AssetRegistry {
+    pub fn space(len: usize) -> usize {
+        8 + 1 + 4 + len * ASSET_ENTRY_SIZE
+    }
+
+    pub fn contains(&self, asset: &Pubkey) -> bool {
+        self.assets.iter().any(|entry| entry.mint == *asset)
+    }
+}
+
+impl Role {
+    pub fn has(&self, role: u8) -> bool {
+        self.roles & role == role
+    }
+}
+
+impl TimelockOperation {
+    /// Checks that this operation queued `action`, that its proposer is still the admin
+    /// and that its delay has passed
+    pub fn check_ready(&self, action: &TimelockAction, vault_state: &VaultState) -> Result<()> {
+        if self.action != *action || self.proposer != vault_state.admin {
+            return Err(MintError::TimelockMismatch.into());
+        }
+
+        if Clock::get()?.unix_timestamp < self.eta {
+            return Err(MintError::TimelockNotReady.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl ExchangeRate {
+    pub const SPACE: usize =
+        8 + 8 + 8 + 32 + 1 + 2 * 24 + (32 + 4 + 4 + 1 + 4 + 2) + 1 + 2 + 2 + 8 + 8 + 4 * 8 + 1 + 2 + 4 + 8;
+
+    /// The vault tokens backed by this asset. The collateral reserved for fulfilled tickets
+    /// is owed to their claimants, whose vault tokens are already burned, so it is left out
+    pub fn backing(&self, program_collateral: u64) -> Result<u128> {
+        let held = program_collateral as u128 + self.held_externally as u128;
+        vault_tokens_for(held, self.deposit_rate, self.decimals)
+    }
+
+    /// Sets the admin rates. A round trip through both rates must never return more
+    /// than was put in, and once listed the rates can only move within the rate limits.
+    pub fn apply_rates(&mut self, deposit_rate: u64, redeem_rate: u64, now: i64) -> Result<()> {
+        if deposit_rate as u128 * redeem_rate as u128 > DECIMALS_SCALAR * DECIMALS_SCALAR {
+            return Err(MintError::RateArbitrage.into());
+        }
+
+        // The first rates of an asset have nothing to be compared against
+        if self.deposit_rate != 0 && self.redeem_rate != 0 {
+            if now < self.rates_updated_at.saturating_add(self.min_rate_interval as i64) {
+                return Err(MintError::RateUpdateTooSoon.into());
+            }
+
+            if self.max_rate_change_bps != 0 {
+                for (new_rate, old_rate) in [(deposit_rate, self.deposit_rate), (redeem_rate, self.redeem_rate)] {
+                    let old_rate = old_rate as u128;
+                    if (new_rate as u128).abs_diff(old_rate) * BPS_SCALAR > old_rate * self.max_rate_change_bps as u128 {
+                        return Err(MintError::RateChangeTooLarge.into());
+                    }
+                }
+            }
+        }
+
+        self.deposit_rate = deposit_rate;
+        self.redeem_rate = redeem_rate;
+        self.rates_updated_at = now;
+
+        Ok(())
+    }
+
+    /// Returns the (deposit, redeem) rates. When an oracle is linked the rates are derived
+    /// from its price, which must be fresh and within the deviation band of the admin set rates.
+    pub fn current_rates(&self, oracle: Option<&AccountInfo>) -> Result<(u64, u64)> {
+        let config = &self.oracle;
+        if config.feed == Pubkey::default() || self.deposit_rate == 0 || self.redeem_rate == 0 {
+            return Ok((self.deposit_rate, self.redeem_rate));
+        }
+
+        let oracle = match oracle {
+            Some(oracle) if oracle.key() == config.feed => oracle,
+            _ => return Err(MintError::OracleMismatch.into()),
+        };
+
+        let data = oracle.try_borrow_data()?;
+        let price = read_i64(&data, config.price_offset)?;
+        let publish_time = read_i64(&data, config.timestamp_offset)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        if publish_time > now {
+            return Err(MintError::FuturePrice.into());
+        }
+
+        if now.saturating_sub(publish_time) > config.max_age as i64 {
+            return Err(MintError::StalePrice.into());
+        }
+
+        if price <= 0 {
+            return Err(MintError::InvalidPrice.into());
+        }
+
+        let price_scalar = 10_u128.pow(config.price_decimals as u32);
+        let deposit_rate = price as u128 * DECIMALS_SCALAR / price_scalar;
+        let redeem_rate = price_scalar * DECIMALS_SCALAR / price as u128;
+
+        for (oracle_rate, admin_rate) in [(deposit_rate, self.deposit_rate), (redeem_rate, self.redeem_rate)] {
+            let admin_rate = admin_rate as u128;
+            if oracle_rate.abs_diff(admin_rate) * BPS_SCALAR > admin_rate * config.max_deviation_bps as u128 {
+                return Err(MintError::PriceOutOfBand.into());
+            }
+        }
+
+        Ok((
+            deposit_rate.try_into().map_err(|_| MintError::InvalidPrice)?,
+            redeem_rate.try_into().map_err(|_| MintError::InvalidPrice)?,
+        ))
+    }
+}
+
+/// Checks that the user can redeem `amt` of the asset right now and returns the
+/// (fee, collateral) of the redemption at the current redeem rate
+fn quote_redeem(
+    vault_state: &VaultState,
+    exchange_rate: &ExchangeRate,
+    permissions: &Permissions,
+    oracle: Option<&AccountInfo>,
+    amt: u64,
+    min_amount_out: u64,
+    expiry_slot: Option<u64>,
+) -> Result<(u64, u64)> {
+    if (vault_state.paused | exchange_rate.paused) & PAUSE_REDEEM != 0 {
+        return Err(MintError::Paused.into());
+    }
+
+    if !exchange_rate.status.can_redeem() {
+        return Err(MintError::RedemptionsDisabled.into());
+    }
+
+    check_expiry(expiry_slot)?;
+
+    let (_, redeem_rate) = exchange_rate.current_rates(oracle)?;
+    if redeem_rate == 0 {
+        return Err(MintError::AssetNotSupported.into());
+    }
+
+    let fee = bps_of(amt, exchange_rate.redeem_fee_bps);
+    let collat = collateral_for(amt - fee, redeem_rate, exchange_rate.decimals)?;
+
+    if !permissions.can_redeem {
+        return Err(MintError::NotAnApprovedRedeemer.into());
+    }
+
+    if collat == 0 {
+        return Err(MintError::AmountTooSmall.into());
+    }
+
+    // Transfer fees only lower what arrives, a direct redeem checks the net amount again
+    if collat < min_amount_out {
+        return Err(MintError::SlippageExceeded.into());
+    }
+
+    Ok((fee, collat))
+}
+
+/// Counts `amount` vault tokens against the vault, asset and user redeem limits
+fn consume_redeem_limits(
+    vault_state: &mut VaultState,
+    exchange_rate: &mut ExchangeRate,
+    permissions: &mut Permissions,
+    amount: u64,
+) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let window = vault_state.limit_window;
+    if !vault_state.redeem_limit.consume(amount, slot, window)
+        || !exchange_rate.redeem_limit.consume(amount, slot, window)
+    {
+        return Err(MintError::MaxRedeemExceeded.into());
+    }
+
+    if !permissions.consume_redeem(amount, slot) {
+        return Err(MintError::UserRedeemQuotaExceeded.into());
+    }
+
+    Ok(())
+}
+
+/// Fails once the current slot is past the caller provided expiry
+fn check_expiry(expiry_slot: Option<u64>) -> Result<()> {
+    if let Some(expiry_slot) = expiry_slot {
+        if Clock::get()?.slot > expiry_slot {
+            return Err(MintError::Expired.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that `quote` is for this side, user and asset, is not expired and was signed
+/// by the quote signer, then consumes its nonce
+fn accept_quote(
+    quote: &Quote,
+    side: QuoteSide,
+    user: &Pubkey,
+    asset: &Pubkey,
+    vault_state: &Account<VaultState>,
+    permissions: &mut Permissions,
+    instructions: Option<&AccountInfo>,
+) -> Result<()> {
+    if vault_state.quote_signer == Pubkey::default() {
+        return Err(MintError::QuotesDisabled.into());
+    }
+
+    if quote.side != side || quote.user != *user || quote.asset != *asset {
+        return Err(MintError::QuoteMismatch.into());
+    }
+
+    if Clock::get()?.unix_timestamp > quote.expiry {
+        return Err(MintError::Expired.into());
+    }
+
+    if quote.nonce <= permissions.quote_nonce {
+        return Err(MintError::QuoteAlreadyUsed.into());
+    }
+    permissions.quote_nonce = quote.nonce;
+
+    let mut message = Vec::with_capacity(QUOTE_DOMAIN.len() + 32 + 32 + 32 + 1 + 4 * 8);
+    message.extend_from_slice(QUOTE_DOMAIN);
+    message.extend_from_slice(vault_state.key().as_ref());
+    quote.serialize(&mut message)?;
+
+    let instructions = instructions.ok_or(MintError::InvalidQuoteSignature)?;
+    verify_ed25519(instructions, &vault_state.quote_signer, &message)
+}
+
+/// Checks that the instruction right before the current one is an Ed25519 program
+/// instruction verifying a single signature of `signer` over `message`
+fn verify_ed25519(instructions: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let current = load_current_index_checked(instructions)?;
+    if current == 0 {
+        return Err(MintError::InvalidQuoteSignature.into());
+    }
+
+    let ix = load_instruction_at_checked(current as usize - 1, instructions)?;
+    if ix.program_id != ed25519_program::ID || !ix.accounts.is_empty() {
+        return Err(MintError::InvalidQuoteSignature.into());
+    }
+
+    // Layout: num_signatures (u8), padding (u8), then one 14 byte offsets struct
+    let data = &ix.data;
+    if data.len() < 16 || data[0] != 1 {
+        return Err(MintError::InvalidQuoteSignature.into());
+    }
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let public_key_offset = read_u16(6) as usize;
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+
+    // The signature, key and message must all live in the Ed25519 instruction itself
+    if read_u16(4) != u16::MAX || read_u16(8) != u16::MAX || read_u16(14) != u16::MAX {
+        return Err(MintError::InvalidQuoteSignature.into());
+    }
+
+    let public_key = data.get(public_key_offset..public_key_offset + 32);
+    let signed = data.get(message_offset..message_offset + message_size);
+    if public_key != Some(signer.as_ref()) || signed != Some(message) {
+        return Err(MintError::InvalidQuoteSignature.into());
+    }
+
+    Ok(())
+}
+
+/// Whether the optional role account of the caller holds `role`
+fn has_role(role: &Option<Account<Role>>, flag: u8) -> bool {
+    role.as_ref().is_some_and(|role| role.has(flag))
+}
+
+/// Counts the approvals of keys that still hold the asset manager role, given their role accounts
+fn count_manager_approvals<'info>(
+    approvals: &[Pubkey],
+    vault: &Pubkey,
+    program_id: &Pubkey,
+    role_accounts: &'info [AccountInfo<'info>],
+) -> Result<usize> {
+    let mut counted: Vec<Pubkey> = Vec::new();
+    for info in role_accounts {
+        // Role accounts are only created at the PDA of their holder in this vault instance
+        let role: Account<Role> = Account::try_from(info)?;
+        let role_key = Pubkey::create_program_address(
+            &[ROLE_SEED, vault.as_ref(), role.holder.as_ref(), &[role.bump]],
+            program_id,
+        )
+        .map_err(|_| MintError::InvalidRoles)?;
+        if role_key == info.key()
+            && approvals.contains(&role.holder)
+            && role.has(ROLE_ASSET_MANAGER)
+            && !counted.contains(&role.holder)
+        {
+            counted.push(role.holder);
+        }
+    }
+
+    Ok(counted.len())
+}
+
+/// Computes `amount * rate / DECIMALS_SCALAR` and moves the result from `from` to `to`
+/// decimals, rounding down once at the end
+fn convert(amount: u128, rate: u64, from: u8, to: u8) -> Result<u128> {
+    let (up, down) = if to >= from { (to - from, 0) } else { (0, from - to) };
+
+    amount
+        .checked_mul(rate as u128)
+        .and_then(|value| value.checked_mul(10_u128.pow(up as u32)))
+        .map(|value| value / (DECIMALS_SCALAR * 10_u128.pow(down as u32)))
+        .ok_or_else(|| MintError::MathOverflow.into())
+}
+
+/// Converts collateral units to vault token units at `rate`
+fn vault_tokens_for(collat: u128, rate: u64, decimals: u8) -> Result<u128> {
+    convert(collat, rate, decimals, VAULT_TOKEN_DECIMALS)
+}
 
-        ctx.accounts.user_permissions.can_redeem = false;
+/// Converts vault token units to collateral units at `rate`
+fn collateral_for(amt: u64, rate: u64, decimals: u8) -> Result<u64> {
+    convert(amt as u128, rate, VAULT_TOKEN_DECIMALS, decimals)?
+        .try_into()
+        .map_err(|_| MintError::MathOverflow.into())
+}
 
-        emit!(RedeemerRemovedEvent{
-            removed: user,
-            removed_by: ctx.accounts.caller.key(),
-        });
+/// Whether `new` caps no more than `old`, where zero means uncapped
+fn cap_tightened(new: u64, old: u64) -> bool {
+    old == 0 || (new != 0 && new <= old)
+}
 
-        Ok(())
+/// Checks that `info` still holds a `T` in the legacy layout of `space` bytes
+fn check_legacy<T: anchor_lang::Discriminator>(info: &AccountInfo, space: usize) -> Result<()> {
+    if info.data_len() != space {
+        return Err(MintError::AlreadyMigrated.into());
     }
 
-    pub fn add_asset_manager(ctx: Context<Managers>, manager: Pubkey) -> Result<()> {
-        let caller = ctx.accounts.caller.key();
-
-        if caller != ctx.accounts.vault_state.admin {
-            return Err(MintError::NotAdmin.into());
-        }
+    if info.try_borrow_data()?[..8] != T::DISCRIMINATOR {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
 
-        let managers = &mut ctx.accounts.vault_state.asset_managers;
+    Ok(())
+}
 
-        if managers.len() > MAX_MANAGER_ADDRESSES {
-            return Err(MintError::MaxArrayLength.into());
+/// Resizes a program owned account to `space`, zero filling any new bytes. The payer tops
+/// up the rent of a larger account and is refunded the excess of a smaller one
+fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    space: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let lamports = account.lamports();
+    match lamports.checked_sub(rent) {
+        Some(excess) => {
+            **account.try_borrow_mut_lamports()? -= excess;
+            **payer.try_borrow_mut_lamports()? += excess;
         }
-
-        if !managers.contains(&manager) {
-            managers.push(manager);
-        } else {
-            return Err(MintError::AlreadyAssetManager.into());
+        None => {
+            let cpi_accounts = anchor_lang::system_program::Transfer {
+                from: payer.clone(),
+                to: account.clone(),
+            };
+            anchor_lang::system_program::transfer(CpiContext::new(system_program.clone(), cpi_accounts), rent - lamports)?;
         }
+    }
 
-        emit!(NewAssetManagerEvent {
-            new_asset_manager: manager,
-            added_by: caller,
-        });
+    account.realloc(space, true)?;
+    Ok(())
+}
 
-        Ok(())
+/// Backing over supply in basis points, zero when nothing is minted
+fn backing_ratio_bps(backing: u128, supply: u64) -> u64 {
+    if supply == 0 {
+        0
+    } else {
+        (backing * BPS_SCALAR / supply as u128).try_into().unwrap_or(u64::MAX)
     }
+}
 
-    pub fn add_role_manager(ctx: Context<Managers>, manager: Pubkey) -> Result<()> {
-        let caller = ctx.accounts.caller.key();
+/// Returns `bps` basis points of `amt`, rounded down
+fn bps_of(amt: u64, bps: u16) -> u64 {
+    (amt as u128 * bps as u128 / BPS_SCALAR) as u64
+}
 
-        if caller != ctx.accounts.vault_state.admin {
-            return Err(MintError::NotAdmin.into());
-        }
+fn read_i64(data: &[u8], offset: u32) -> Result<i64> {
+    let offset = offset as usize;
+    let bytes = data
+        .get(offset..offset + 8)
+        .ok_or(MintError::InvalidOracleData)?;
 
-        let managers = &mut ctx.accounts.vault_state.role_managers;
+    Ok(i64::from_le_bytes(bytes.try_into().unwrap()))
+}
 
-        if managers.len() > MAX_MANAGER_ADDRESSES {
-            return Err(MintError::MaxArrayLength.into());
-        }
+impl Permissions {
+    pub const SPACE: usize = 8 + 32 + 1 + 1 + 8 + 8 + 2 * 24 + 4 * 8;
 
-        if !managers.contains(&manager) {
-            managers.push(manager);
-        } else {
-            return Err(MintError::AlreadyRoleManager.into());
+    /// Whether the user used a quote or counts towards a lifetime cap
+    pub fn has_history(&self) -> bool {
+        self.quote_nonce != 0 || self.total_minted != 0 || self.total_redeemed != 0
+    }
+
+    /// Adds `amt` to the user's mint usage. Returns false if a quota would be exceeded.
+    pub fn consume_mint(&mut self, amt: u64, now: u64) -> bool {
+        let total = self.total_minted.saturating_add(amt);
+        if self.max_total_mint != 0 && total > self.max_total_mint {
+            return false;
         }
 
-        emit!(NewRoleManagerEvent {
-            new_role_manager: manager,
-            added_by: caller,
-        });
+        if self.quota_window != 0 && !self.mint_quota.consume(amt, now, self.quota_window) {
+            return false;
+        }
 
-        Ok(())
+        self.total_minted = total;
+        true
     }
 
-    pub fn remove_asset_manager(ctx: Context<Managers>, manager: Pubkey) -> Result<()> {
-        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
-            return Err(MintError::NotAdmin.into());
+    /// Adds `amt` to the user's redeem usage. Returns false if a quota would be exceeded.
+    pub fn consume_redeem(&mut self, amt: u64, now: u64) -> bool {
+        let total = self.total_redeemed.saturating_add(amt);
+        if self.max_total_redeem != 0 && total > self.max_total_redeem {
+            return false;
         }
 
-        let managers = &mut ctx.accounts.vault_state.asset_managers;
-
-        if let Some(i) = managers.iter().position(|&x| x == manager) {
-            managers.swap_remove(i);
-        } else {
-            return Err(MintError::NotManagerYet.into());
+        if self.quota_window != 0 && !self.redeem_quota.consume(amt, now, self.quota_window) {
+            return false;
         }
 
-        emit!(AssetManagerRemovedEvent{
-            asset_manager_removed: manager,
-            removed_by: ctx.accounts.caller.key(),
-        });
+        self.total_redeemed = total;
+        true
+    }
+}
+
+impl WithdrawPolicy {
+    pub fn update(&mut self, allowed_mints: &[Pubkey], max_per_withdraw: u64, daily_limit: u64) -> Result<()> {
+        if allowed_mints.len() > MAX_POLICY_ASSETS {
+            return Err(MintError::MaxArrayLength.into());
+        }
 
+        self.allowed_mints = allowed_mints.to_vec();
+        self.max_per_withdraw = max_per_withdraw;
+        self.daily_limit.max = daily_limit;
         Ok(())
     }
 
-    pub fn remove_role_manager(ctx: Context<Managers>, manager: Pubkey) -> Result<()> {
-        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
-            return Err(MintError::NotAdmin.into());
-        }
+    /// Whether the new policy allows no more than the current one
+    pub fn is_tightened_by(&self, allowed_mints: &[Pubkey], max_per_withdraw: u64, daily_limit: u64) -> bool {
+        allowed_mints.iter().all(|mint| self.allowed_mints.contains(mint))
+            && cap_tightened(max_per_withdraw, self.max_per_withdraw)
+            && cap_tightened(daily_limit, self.daily_limit.max)
+    }
+}
 
-        let managers = &mut ctx.accounts.vault_state.role_managers;
+impl WindowLimit {
+    /// Adds `amt` to the usage of the current window, starting a new window at `now`
+    /// once `window` has elapsed. Returns false if the cap would be exceeded.
+    pub fn consume(&mut self, amt: u64, now: u64, window: u64) -> bool {
+        if now >= self.window_start.saturating_add(window) {
+            self.window_start = now;
+            self.used = 0;
+        }
 
-        if let Some(i) = managers.iter().position(|&x| x == manager) {
-            managers.swap_remove(i);
-        } else {
-            return Err(MintError::NotManagerYet.into());
+        match self.used.checked_add(amt) {
+            Some(used) if self.max == 0 || used <= self.max => {
+                self.used = used;
+                true
+            }
+            _ => false,
         }
+    }
+}
 
-        emit!(RoleManagerRemovedEvent{
-            role_manager_removed: manager,
-            removed_by: ctx.accounts.caller.key(),
-        });
+#[event]
+pub struct AssetModifiedEvent {
+    who: Pubkey,
+    asset: Pubkey,
+    deposit_rate: u64,
+    redeem_rate: u64,
+    salt: [u8; 8],
+}
 
-        Ok(())
-    }
+#[event]
+pub struct RateLimitsUpdatedEvent {
+    who: Pubkey,
+    asset: Pubkey,
+    max_change_bps: u16,
+    min_interval: u32,
+    salt: [u8; 8],
+}
 
-    pub fn add_withdraw_address(ctx: Context<WithdrawAddresses>, address: Pubkey) -> Result<()> {
-        let caller = ctx.accounts.caller.key();
+#[event]
+pub struct AssetRegisteredEvent {
+    asset: Pubkey,
+    exchange_rate: Pubkey,
+    decimals: u8,
+    salt: [u8; 8],
+}
 
-        if caller != ctx.accounts.vault_state.admin {
-            return Err(MintError::NotAdmin.into());
-        }
+#[event]
+pub struct AssetRetiredEvent {
+    asset: Pubkey,
+    exchange_rate: Pubkey,
+    program_collateral: Pubkey,
+    rent_receiver: Pubkey,
+    salt: [u8; 8],
+}
 
-        let withdraw_addresses = &mut ctx.accounts.vault_state.withdraw_addresses;
+#[event]
+pub struct AssetStatusUpdatedEvent {
+    asset: Pubkey,
+    status: AssetStatus,
+    updated_by: Pubkey,
+    salt: [u8; 8],
+}
 
-        if withdraw_addresses.len() > MAX_WITHDRAW_ADRESSES {
-            return Err(MintError::MaxArrayLength.into());
-        }
+#[event]
+pub struct LimitsUpdatedEvent {
+    who: Pubkey,
+    window: u64,
+    max_mint: u64,
+    max_redeem: u64,
+    salt: [u8; 8],
+}
 
-        if !withdraw_addresses.contains(&address) {
-            withdraw_addresses.push(address);
-        } else {
-            return Err(MintError::AlreadyWithdrawer.into());
-        }
+#[event]
+pub struct AssetLimitsUpdatedEvent {
+    who: Pubkey,
+    asset: Pubkey,
+    max_mint: u64,
+    max_redeem: u64,
+    salt: [u8; 8],
+}
 
-        emit!(WithdrawAddressAdded {
-            address: address,
-            added_by: caller,
-        });
+#[event]
+pub struct OracleUpdatedEvent {
+    who: Pubkey,
+    asset: Pubkey,
+    feed: Pubkey,
+    max_age: u32,
+    max_deviation_bps: u16,
+    salt: [u8; 8],
+}
 
-        Ok(())
-    }
+#[event]
+pub struct PausedEvent {
+    /// None when the whole vault is paused
+    asset: Option<Pubkey>,
+    flags: u8,
+    paused_by: Pubkey,
+    salt: [u8; 8],
+}
 
-    pub fn remove_withdraw_address(ctx: Context<WithdrawAddresses>, address: Pubkey) -> Result<()> {
-        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
-            return Err(MintError::NotAdmin.into());
-        }
+#[event]
+pub struct UnpausedEvent {
+    asset: Option<Pubkey>,
+    flags: u8,
+    unpaused_by: Pubkey,
+    salt: [u8; 8],
+}
 
-        let withdraw_addresses = &mut ctx.accounts.vault_state.withdraw_addresses;
+#[event]
+pub struct OperationQueuedEvent {
+    id: u64,
+    action: TimelockAction,
+    eta: i64,
+    proposer: Pubkey,
+    salt: [u8; 8],
+}
 
-        if let Some(i) = withdraw_addresses.iter().position(|&x| x == address) {
-            withdraw_addresses.swap_remove(i);
-        } else {
-            return Err(MintError::NotWithdrawerYet.into());
-        }
+#[event]
+pub struct OperationCancelledEvent {
+    id: u64,
+    cancelled_by: Pubkey,
+    salt: [u8; 8],
+}
 
-        emit!(WithdrawAddressRemoved{
-            address: address,
-            removed_by: ctx.accounts.caller.key(),
-        });
+#[event]
+pub struct OperationExecutedEvent {
+    id: u64,
+    executed_by: Pubkey,
+    salt: [u8; 8],
+}
 
-        Ok(())
-    }
+#[event]
+pub struct TimelockDelayUpdatedEvent {
+    delay: u32,
+    proposed_by: Pubkey,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct MetadataUpdatedEvent {
+    name: String,
+    symbol: String,
+    uri: String,
+    updated_by: Pubkey,
+    salt: [u8; 8],
+}
 
+#[event]
+pub struct TreasuryUpdatedEvent {
+    treasury: Pubkey,
+    updated_by: Pubkey,
+    salt: [u8; 8],
+}
 
-    pub fn transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
-        if ctx.accounts.caller.key() != ctx.accounts.vault_state.admin {
-            return Err(MintError::NotAdmin.into());
-        }
+#[event]
+pub struct FeesUpdatedEvent {
+    asset: Pubkey,
+    mint_fee_bps: u16,
+    redeem_fee_bps: u16,
+    proposed_by: Pubkey,
+    salt: [u8; 8],
+}
 
-        let vault_state = &mut ctx.accounts.vault_state;
-        vault_state.admin = new_admin;
+#[event]
+pub struct QuoteSignerUpdatedEvent {
+    previous: Pubkey,
+    signer: Pubkey,
+    proposed_by: Pubkey,
+    salt: [u8; 8],
+}
 
-        emit!(AdminTransferEvent{
-            old_admin: ctx.accounts.caller.key(),
-            new_admin: new_admin,
-        });
+#[event]
+pub struct HolderFrozenEvent {
+    account: Pubkey,
+    owner: Pubkey,
+    frozen_by: Pubkey,
+    salt: [u8; 8],
+}
 
-        Ok(())
-    }
+#[event]
+pub struct HolderThawedEvent {
+    account: Pubkey,
+    owner: Pubkey,
+    thawed_by: Pubkey,
+    salt: [u8; 8],
 }
 
-impl VaultState {
-    pub fn new(&mut self) {
-        self.asset_managers = Vec::with_capacity(20);
-        self.role_managers = Vec::with_capacity(20);
-        self.withdraw_addresses = Vec::with_capacity(50);
-    }
+#[event]
+pub struct SeizedEvent {
+    account: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+    /// None when the seized balance was burned
+    recovery: Option<Pubkey>,
+    proposed_by: Pubkey,
+    salt: [u8; 8],
 }
 
 #[event]
-pub struct AssetModifiedEvent {
-    who: Pubkey,
+pub struct QuoteFilledEvent {
+    user: Pubkey,
     asset: Pubkey,
-    deposit_rate: u64,
-    redeem_rate: u64,
+    side: QuoteSide,
+    amount_in: u64,
+    amount_out: u64,
+    nonce: u64,
+    salt: [u8; 8],
 }
 
 #[event]
@@ -525,6 +3135,32 @@ pub struct DepositEvent {
     who: Pubkey,
     token_mint: Pubkey,
     amt: u64,
+    /// Vault tokens minted to the treasury
+    fee: u64,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct DepositForEvent {
+    /// The minter whose collateral was deposited
+    payer: Pubkey,
+    /// The owner of the vault token account that was minted to
+    receiver: Pubkey,
+    token_account: Pubkey,
+    token_mint: Pubkey,
+    amt: u64,
+    fee: u64,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct WithdrawPolicyUpdatedEvent {
+    destination: Pubkey,
+    allowed_mints: Vec<Pubkey>,
+    max_per_withdraw: u64,
+    daily_limit: u64,
+    updated_by: Pubkey,
+    salt: [u8; 8],
 }
 
 #[event]
@@ -532,6 +3168,98 @@ pub struct WithdrawEvent {
     who: Pubkey,
     token_mint: Pubkey,
     amt: u64,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct WithdrawThresholdUpdatedEvent {
+    threshold: u8,
+    proposed_by: Pubkey,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct WithdrawProposedEvent {
+    id: u64,
+    proposer: Pubkey,
+    asset: Pubkey,
+    amount: u64,
+    destination: Pubkey,
+    deadline: i64,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct WithdrawApprovedEvent {
+    id: u64,
+    approver: Pubkey,
+    approvals: u8,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct WithdrawCancelledEvent {
+    id: u64,
+    cancelled_by: Pubkey,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct WithdrawExecutedEvent {
+    id: u64,
+    executed_by: Pubkey,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct RedeemRequestedEvent {
+    id: u64,
+    asset: Pubkey,
+    owner: Pubkey,
+    /// Escrowed vault tokens
+    amount: u64,
+    collateral: u64,
+    /// Vault tokens moved to the treasury
+    fee: u64,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct RedeemFulfilledEvent {
+    id: u64,
+    asset: Pubkey,
+    owner: Pubkey,
+    collateral: u64,
+    fulfilled_by: Pubkey,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct RedeemClaimedEvent {
+    id: u64,
+    asset: Pubkey,
+    owner: Pubkey,
+    collateral: u64,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct RedeemCancelledEvent {
+    id: u64,
+    asset: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct RepayEvent {
+    asset: Pubkey,
+    amt: u64,
+    /// Collateral still held externally after the repayment
+    held_externally: u64,
+    repaid_by: Pubkey,
+    salt: [u8; 8],
 }
 
 #[event]
@@ -539,72 +3267,148 @@ pub struct RedeemEvent {
     who: Pubkey,
     token_mint: Pubkey,
     amt: u64,
+    /// Vault tokens sent to the treasury instead of being burned
+    fee: u64,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct RedeemToEvent {
+    /// The redeemer whose vault tokens were burned
+    payer: Pubkey,
+    /// The owner of the collateral account that was paid out
+    receiver: Pubkey,
+    token_account: Pubkey,
+    token_mint: Pubkey,
+    amt: u64,
+    fee: u64,
+    salt: [u8; 8],
 }
 
 #[event]
 pub struct NewMinterEvent {
     new_minter: Pubkey,
     added_by: Pubkey,
+    salt: [u8; 8],
 }
 
 #[event]
 pub struct WithdrawAddressAdded {
     address: Pubkey,
     added_by: Pubkey,
+    salt: [u8; 8],
 }
 
 #[event]
 pub struct WithdrawAddressRemoved {
     address: Pubkey,
     removed_by: Pubkey,
+    salt: [u8; 8],
 }
 
 #[event]
 pub struct MinterRemovedEvent {
     removed: Pubkey,
     removed_by: Pubkey,
+    salt: [u8; 8],
 }
 
 #[event]
 pub struct NewRedeemerEvent {
     new_redeemer: Pubkey,
     added_by: Pubkey,
+    salt: [u8; 8],
 }
 
 #[event]
 pub struct RedeemerRemovedEvent {
     removed: Pubkey,
     removed_by: Pubkey,
+    salt: [u8; 8],
 }
 
 #[event]
-pub struct NewAssetManagerEvent {
-    new_asset_manager: Pubkey,
-    added_by: Pubkey,
+pub struct PermissionsClosedEvent {
+    user: Pubkey,
+    rent_receiver: Pubkey,
+    closed_by: Pubkey,
+    salt: [u8; 8],
 }
 
 #[event]
-pub struct NewRoleManagerEvent {
-    new_role_manager: Pubkey,
-    added_by: Pubkey,
+pub struct UserQuotasUpdatedEvent {
+    user: Pubkey,
+    window: u64,
+    max_mint: u64,
+    max_redeem: u64,
+    max_total_mint: u64,
+    max_total_redeem: u64,
+    updated_by: Pubkey,
+    salt: [u8; 8],
 }
 
 #[event]
-pub struct AssetManagerRemovedEvent {
-    asset_manager_removed: Pubkey,
-    removed_by: Pubkey,
+pub struct RoleGrantedEvent {
+    holder: Pubkey,
+    granted: u8,
+    /// All roles of the holder after the grant
+    roles: u8,
+    granted_by: Pubkey,
+    salt: [u8; 8],
 }
 
 #[event]
-pub struct RoleManagerRemovedEvent {
-    role_manager_removed: Pubkey,
-    removed_by: Pubkey,
+pub struct RoleRevokedEvent {
+    holder: Pubkey,
+    revoked: u8,
+    /// All roles of the holder after the revoke
+    roles: u8,
+    revoked_by: Pubkey,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct RoleClosedEvent {
+    holder: Pubkey,
+    closed_by: Pubkey,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct VaultStateMigratedEvent {
+    vault_state: Pubkey,
+    vault_token_mint: Pubkey,
+    admin: Pubkey,
+    asset_managers: Vec<Pubkey>,
+    role_managers: Vec<Pubkey>,
+    withdraw_addresses: Vec<Pubkey>,
+}
+
+#[event]
+pub struct PermissionsMigratedEvent {
+    user: Pubkey,
+    migrated_by: Pubkey,
+}
+
+#[event]
+pub struct AdminProposedEvent {
+    admin: Pubkey,
+    pending_admin: Pubkey,
+    salt: [u8; 8],
 }
 
 #[event]
 pub struct AdminTransferEvent{
     old_admin: Pubkey,
     new_admin: Pubkey,
+    salt: [u8; 8],
+}
+
+#[event]
+pub struct AdminTransferCancelledEvent {
+    admin: Pubkey,
+    cancelled_admin: Pubkey,
+    salt: [u8; 8],
 }
 
 #[error_code]
@@ -639,9 +3443,9 @@ pub enum MintError {
     NotManagerYet,
     #[msg("The provided key is not yet a whitelisted withdraw address")]
     NotWithdrawerYet,
-    #[msg("Max mint for this block has been exceeded")]
+    #[msg("Max mint for this window has been exceeded")]
     MaxMintExceeded,
-    #[msg("Max redeem for this block has been exceeded")]
+    #[msg("Max redeem for this window has been exceeded")]
     MaxRedeemExceeded,
     #[msg("Asset not supported by mint vault")]
     AssetNotSupported,
@@ -649,4 +3453,295 @@ pub enum MintError {
     AssetAlreadySupported,
     #[msg("Max array length has been exceeded")]
     MaxArrayLength,
+    #[msg("Limit window must be non-zero")]
+    InvalidLimitWindow,
+    #[msg("Invalid oracle configuration")]
+    InvalidOracleConfig,
+    #[msg("The provided oracle is not linked to this asset")]
+    OracleMismatch,
+    #[msg("The oracle account data is too short for the configured layout")]
+    InvalidOracleData,
+    #[msg("The oracle price is stale")]
+    StalePrice,
+    #[msg("The oracle price must be positive")]
+    InvalidPrice,
+    #[msg("The oracle price is outside the allowed band")]
+    PriceOutOfBand,
+    #[msg("The oracle price is published in the future")]
+    FuturePrice,
+    #[msg("There is no pending admin")]
+    NoPendingAdmin,
+    #[msg("The caller is not the pending admin")]
+    NotPendingAdmin,
+    #[msg("The caller is not the admin or the guardian")]
+    NotAdminOrGuardian,
+    #[msg("The operation does not match the queued action")]
+    TimelockMismatch,
+    #[msg("The operation delay has not passed yet")]
+    TimelockNotReady,
+    #[msg("The timelock delay must be between the minimum and maximum")]
+    InvalidTimelockDelay,
+    #[msg("The withdraw threshold must be between 2 and the max number of approvals")]
+    InvalidWithdrawThreshold,
+    #[msg("The proposal deadline must be in the future and within the max proposal lifetime")]
+    InvalidDeadline,
+    #[msg("The withdraw proposal has expired")]
+    ProposalExpired,
+    #[msg("The caller already approved this withdraw proposal")]
+    AlreadyApproved,
+    #[msg("The withdraw proposal does not have enough approvals")]
+    NotEnoughApprovals,
+    #[msg("The accounts do not match the withdraw proposal")]
+    ProposalMismatch,
+    #[msg("The caller is not the proposer or the admin")]
+    NotProposerOrAdmin,
+    #[msg("The requested operation is paused")]
+    Paused,
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+    #[msg("The fee exceeds the maximum")]
+    InvalidFee,
+    #[msg("The treasury account is missing or does not match the vault treasury")]
+    InvalidTreasury,
+    #[msg("The amount out is below the minimum requested")]
+    SlippageExceeded,
+    #[msg("The request has expired")]
+    Expired,
+    #[msg("Quotes are disabled")]
+    QuotesDisabled,
+    #[msg("The quote is for a different user, asset or side")]
+    QuoteMismatch,
+    #[msg("The quote nonce was already used")]
+    QuoteAlreadyUsed,
+    #[msg("The quote is not signed by the quote signer")]
+    InvalidQuoteSignature,
+    #[msg("The vault did not receive the quoted amount")]
+    QuoteAmountNotReceived,
+    #[msg("Redeem tickets must be fulfilled in order")]
+    TicketOutOfOrder,
+    #[msg("The redeem ticket is not pending")]
+    TicketNotPending,
+    #[msg("The redeem ticket has not been fulfilled")]
+    TicketNotFulfilled,
+    #[msg("The caller does not own the redeem ticket")]
+    NotTicketOwner,
+    #[msg("Mint exceeds the user's quota")]
+    UserMintQuotaExceeded,
+    #[msg("Redeem exceeds the user's quota")]
+    UserRedeemQuotaExceeded,
+    #[msg("Backing accounts must be unique (exchange rate, program collateral) pairs of every listed asset")]
+    InvalidBackingAccounts,
+    #[msg("The destination may not receive this asset")]
+    AssetNotAllowed,
+    #[msg("Withdraw exceeds the destination's per withdraw max")]
+    MaxWithdrawExceeded,
+    #[msg("Withdraw exceeds the destination's daily limit")]
+    DailyWithdrawLimitExceeded,
+    #[msg("Loosening a limit has to go through the timelock")]
+    LooseningTimelocked,
+    #[msg("Roles must be a non-empty set of known role flags")]
+    InvalidRoles,
+    #[msg("The role account still holds roles")]
+    RoleNotEmpty,
+    #[msg("Deposits of this asset are disabled")]
+    DepositsDisabled,
+    #[msg("Redemptions of this asset are disabled")]
+    RedemptionsDisabled,
+    #[msg("The asset is retired")]
+    AssetRetired,
+    #[msg("Use retire_asset to retire an asset")]
+    InvalidAssetStatus,
+    #[msg("The asset must be redeem-only before it can be retired")]
+    AssetNotRedeemOnly,
+    #[msg("Redemptions of the asset are still outstanding")]
+    RedemptionsOutstanding,
+    #[msg("The vault still holds collateral of the asset")]
+    CollateralRemaining,
+    #[msg("The redeem reserve is not a token account of the collateral token program")]
+    InvalidRedeemReserve,
+    #[msg("The user can still mint or redeem")]
+    PermissionsActive,
+    #[msg("The user used quotes or has lifetime totals to keep")]
+    PermissionsHaveHistory,
+    #[msg("The deposit and redeem rates allow a profitable round trip")]
+    RateArbitrage,
+    #[msg("The rates changed more than the asset allows per update")]
+    RateChangeTooLarge,
+    #[msg("The rates were updated too recently")]
+    RateUpdateTooSoon,
+    #[msg("The token account is not owned by the caller")]
+    NotCallerTokenAccount,
+    #[msg("The asset has more decimals than the vault supports")]
+    UnsupportedDecimals,
+    #[msg("Amount overflows")]
+    MathOverflow,
+    #[msg("The amount converts to zero at the current rate")]
+    AmountTooSmall,
+    #[msg("The all zero salt is reserved for the legacy vault")]
+    ReservedSalt,
+    #[msg("The account has already been migrated")]
+    AlreadyMigrated,
+    #[msg("The legacy vault token has no freeze authority, permanent delegate or mutable metadata")]
+    UnsupportedByLegacyVault,
+    #[msg("The holder account is already frozen")]
+    AccountFrozen,
+    #[msg("The holder account is not frozen")]
+    AccountNotFrozen,
+    #[msg("The recovery account does not match the queued operation")]
+    InvalidRecoveryAccount,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    fn overflow() -> Error {
+        MintError::MathOverflow.into()
+    }
+
+    #[test]
+    fn window_limit_of_zero_is_uncapped() {
+        let mut limit = WindowLimit::default();
+        assert!(limit.consume(u64::MAX, 0, 10));
+
+        limit.max = 5;
+        assert!(limit.consume(5, 10, 10));
+        assert!(!limit.consume(1, 15, 10));
+        assert!(limit.consume(1, 20, 10));
+    }
+
+    #[test]
+    fn convert_at_unit_rate_keeps_the_amount() {
+        assert_eq!(convert(1, DECIMALS_SCALAR as u64, 9, 9).unwrap(), 1);
+        assert_eq!(convert(u64::MAX as u128, DECIMALS_SCALAR as u64, 9, 9).unwrap(), u64::MAX as u128);
+    }
+
+    #[test]
+    fn convert_at_boundary_rates() {
+        assert_eq!(convert(u64::MAX as u128, 0, 9, 9).unwrap(), 0);
+        // A rate of one scaled unit rounds anything below DECIMALS_SCALAR down to zero
+        assert_eq!(convert(DECIMALS_SCALAR - 1, 1, 9, 9).unwrap(), 0);
+        assert_eq!(convert(DECIMALS_SCALAR, 1, 9, 9).unwrap(), 1);
+        assert_eq!(
+            convert(u64::MAX as u128, u64::MAX, 9, 9).unwrap(),
+            u64::MAX as u128 * u64::MAX as u128 / DECIMALS_SCALAR
+        );
+    }
+
+    #[test]
+    fn convert_scales_between_decimals() {
+        let rate = DECIMALS_SCALAR as u64;
+        assert_eq!(convert(1, rate, 6, 9).unwrap(), 1_000);
+        assert_eq!(convert(1_000, rate, 9, 6).unwrap(), 1);
+        assert_eq!(convert(999, rate, 9, 6).unwrap(), 0);
+        assert_eq!(convert(10_u128.pow(18), rate, 18, 9).unwrap(), 10_u128.pow(9));
+        assert_eq!(convert(1, rate, 0, 18).unwrap(), 10_u128.pow(18));
+    }
+
+    #[test]
+    fn convert_overflows() {
+        assert_eq!(convert(u128::MAX, 2, 9, 9).unwrap_err(), overflow());
+        assert_eq!(convert(u64::MAX as u128, u64::MAX, 0, 9).unwrap_err(), overflow());
+        assert_eq!(convert(u64::MAX as u128, u64::MAX, 0, 18).unwrap_err(), overflow());
+    }
+
+    #[test]
+    fn vault_tokens_for_u64_max_collateral() {
+        let rate = DECIMALS_SCALAR as u64;
+        assert_eq!(vault_tokens_for(u64::MAX as u128, rate, 18).unwrap(), u64::MAX as u128 / DECIMALS_SCALAR);
+        assert_eq!(vault_tokens_for(u64::MAX as u128, rate, 0).unwrap(), u64::MAX as u128 * DECIMALS_SCALAR);
+    }
+
+    #[test]
+    fn collateral_for_rejects_results_above_u64() {
+        let rate = DECIMALS_SCALAR as u64;
+        assert_eq!(collateral_for(u64::MAX, rate, VAULT_TOKEN_DECIMALS).unwrap(), u64::MAX);
+        assert_eq!(collateral_for(u64::MAX, rate, MAX_COLLATERAL_DECIMALS).unwrap_err(), overflow());
+        assert_eq!(collateral_for(u64::MAX, u64::MAX, VAULT_TOKEN_DECIMALS).unwrap_err(), overflow());
+        assert_eq!(collateral_for(u64::MAX, 1, 0).unwrap(), u64::MAX / (DECIMALS_SCALAR * DECIMALS_SCALAR) as u64);
+    }
+
+    #[test]
+    fn unclaimed_tickets_do_not_raise_the_backing_ratio() {
+        let mut rate = ExchangeRate {
+            deposit_rate: DECIMALS_SCALAR as u64,
+            decimals: VAULT_TOKEN_DECIMALS,
+            held_externally: 600,
+            ..Default::default()
+        };
+        let before = backing_ratio_bps(rate.backing(400).unwrap(), 1_000);
+        assert_eq!(before, 10_000);
+
+        // A ticket of 300 vault tokens is fulfilled out of custody, its vault tokens are burned
+        rate.held_externally -= 300;
+        rate.reserved += 300;
+        assert_eq!(backing_ratio_bps(rate.backing(400).unwrap(), 700), before);
+    }
+
+    #[test]
+    fn legacy_salt_keeps_the_unscoped_seeds() {
+        let legacy = Pubkey::find_program_address(&[VAULT_STATE_SEED, LEGACY_SALT.scope()], &ID);
+        assert_eq!(legacy, Pubkey::find_program_address(&[VAULT_STATE_SEED], &ID));
+
+        let salt = [1; 8];
+        assert_eq!(salt.scope(), &salt);
+    }
+
+    #[test]
+    fn legacy_vault_state_fixture_migrates() {
+        let legacy = LegacyVaultState {
+            vault_token_mint: Pubkey::new_unique(),
+            asset_managers: vec![Pubkey::new_unique()],
+            role_managers: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            withdraw_addresses: vec![Pubkey::new_unique()],
+            admin: Pubkey::new_unique(),
+            bump: 254,
+        };
+        let mut data = VaultState::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        data.resize(LEGACY_VAULT_STATE_SPACE, 0);
+
+        let mut migrated = Vec::new();
+        LegacyVaultState::read(&data).unwrap().migrate(MAX_TIMELOCK_DELAY).try_serialize(&mut migrated).unwrap();
+        assert_eq!(migrated.len(), VaultState::SPACE);
+
+        let state = VaultState::try_deserialize(&mut &migrated[..]).unwrap();
+        assert_eq!(state.vault_token_mint, legacy.vault_token_mint);
+        assert_eq!(state.admin, legacy.admin);
+        assert_eq!(state.bump, legacy.bump);
+        assert_eq!(state.timelock_delay, MAX_TIMELOCK_DELAY);
+        assert_eq!(state.withdraw_threshold, MIN_WITHDRAW_THRESHOLD);
+        assert!(state.salt.scope().is_empty());
+    }
+
+    #[test]
+    fn legacy_exchange_rate_and_permissions_fixtures_grow_into_the_current_layouts() {
+        let asset = Pubkey::new_unique();
+        let mut data = ExchangeRate::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(asset.as_ref());
+        data.extend_from_slice(&2_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(&500_000_000u64.to_le_bytes());
+        assert_eq!(data.len(), LEGACY_EXCHANGE_RATE_SPACE);
+
+        data.resize(ExchangeRate::SPACE, 0);
+        let exchange_rate = ExchangeRate::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(exchange_rate.asset, asset);
+        assert_eq!(exchange_rate.deposit_rate, 2_000_000_000);
+        assert_eq!(exchange_rate.redeem_rate, 500_000_000);
+        assert_eq!(exchange_rate.status, AssetStatus::Active);
+
+        let user = Pubkey::new_unique();
+        let mut data = Permissions::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(user.as_ref());
+        data.extend_from_slice(&[1, 0]);
+        assert_eq!(data.len(), LEGACY_PERMISSIONS_SPACE);
+
+        data.resize(Permissions::SPACE, 0);
+        let permissions = Permissions::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(permissions.key, user);
+        assert!(permissions.can_mint);
+        assert!(!permissions.can_redeem);
+        assert!(!permissions.has_history());
+    }
 }
@@ -13,6 +13,8 @@ import {
 import { assert } from "chai";
 import { BN } from "bn.js";
 
+const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
 describe("stake", () => {
   // Metaplex metadata
   const metadata = {
@@ -100,6 +102,7 @@ describe("stake", () => {
     console.log("Admin: ", adminKey.toString());
 
     await program.methods.initializeProgramAccounts(salt, metadata).accounts({
+      tokenProgram: TOKEN_PROGRAM_ID,
       depositToken: unstakedMint.publicKey,
       caller: adminKey,
     }).rpc().catch(e => console.error(e));
@@ -140,6 +143,7 @@ describe("stake", () => {
 
     // Stake as user
     await program.methods.stake(salt, one).accounts({
+      tokenProgram: TOKEN_PROGRAM_ID,
       userDepositTokenAccount: userUnstaked,
       userStakingTokenAccount: userStaked,
       user: user.publicKey,
@@ -147,6 +151,7 @@ describe("stake", () => {
 
     // Stake as user
     await program.methods.stake(salt, stake).accounts({
+      tokenProgram: TOKEN_PROGRAM_ID,
       userDepositTokenAccount: userUnstaked,
       userStakingTokenAccount: userStaked,
       user: user.publicKey,
@@ -186,6 +191,7 @@ describe("stake", () => {
 
     // Reward unstaked tokens to vault as user
     await program.methods.reward(reward, salt).accounts({
+      tokenProgram: TOKEN_PROGRAM_ID,
       callerTokenAccount: userUnstaked,
       caller: user.publicKey,
     }).signers([user]).rpc().catch(e => console.error(e));
@@ -220,6 +226,7 @@ describe("stake", () => {
 
     // Unstake as user one
     await program.methods.startUnstake(salt, unstake).accounts({
+      tokenProgram: TOKEN_PROGRAM_ID,
       userDepositTokenAccount: userUnstaked,
       userStakingTokenAccount: userStaked,
       user: user.publicKey
@@ -236,6 +243,7 @@ describe("stake", () => {
 
     // Unstake some as user two
     await program.methods.startUnstake(salt, unstakeTwo).accounts({
+      tokenProgram: TOKEN_PROGRAM_ID,
       userDepositTokenAccount: userTwoUnstaked,
       userStakingTokenAccount: userTwoStaked,
       user: userTwo.publicKey
@@ -243,6 +251,7 @@ describe("stake", () => {
 
     // Unstake remainder as user two 
     await program.methods.startUnstake(salt, unstakeTwo).accounts({
+      tokenProgram: TOKEN_PROGRAM_ID,
       userDepositTokenAccount: userTwoUnstaked,
       userStakingTokenAccount: userTwoStaked,
       user: userTwo.publicKey
@@ -267,18 +276,21 @@ describe("stake", () => {
 
     // Finish unstaking
     await program.methods.unstake(salt, unstakeTwo).accounts({
+      tokenProgram: TOKEN_PROGRAM_ID,
       userDepositTokenAccount: userTwoUnstaked,
       userStakingTokenAccount: userTwoStaked,
       user: userTwo.publicKey
     }).signers([userTwo]).rpc().catch(e => console.error(e));
 
     await program.methods.unstake(salt, unstake).accounts({
+      tokenProgram: TOKEN_PROGRAM_ID,
       userDepositTokenAccount: userUnstaked,
       userStakingTokenAccount: userStaked,
       user: user.publicKey
     }).signers([user]).rpc().catch(e => console.error(e));
 
     await program.methods.unstake(salt, unstakeTwo).accounts({
+      tokenProgram: TOKEN_PROGRAM_ID,
       userDepositTokenAccount: userTwoUnstaked,
       userStakingTokenAccount: userTwoStaked,
       user: userTwo.publicKey
@@ -313,13 +325,45 @@ describe("stake", () => {
     console.log("Unblacklisted user: ", adminKey.toString());
   });
 
+  it("Updates the staking token metadata", async () => {
+    const rebrand = { name: 'Staked Gold', symbol: 'SGOLD', uri: metadata.uri };
+    try {
+      await program.methods.updateMetadata(salt, rebrand).accounts({
+        caller: user.publicKey,
+      }).signers([user]).rpc();
+      assert.fail("Metadata update by a non admin should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "NotAdmin");
+    }
+
+    await program.methods.updateMetadata(salt, rebrand).rpc();
+
+    const [metadataPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), stakingTokenPDA.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+    const metadataAccount = await program.provider.connection.getAccountInfo(metadataPDA);
+    assert.ok(metadataAccount.data.includes(Buffer.from(rebrand.name)));
+    assert.ok(metadataAccount.data.includes(Buffer.from(rebrand.symbol)));
+  });
+
   it("Transfer admin", async () => {
-    await program.methods.transferAdmin(userTwo.publicKey, salt).rpc().catch(e => console.error(e));
+    await program.methods.proposeAdmin(userTwo.publicKey, salt).rpc();
+    console.log("Proposed admin: ", userTwo.publicKey.toString());
+    await program.methods.acceptAdmin(salt).accounts({
+      caller: userTwo.publicKey
+    }).signers([userTwo]).rpc();
     console.log("Transferred admin to: ", userTwo.publicKey.toString());
-    await program.methods.transferAdmin(adminKey, salt).accounts({
+
+    await program.methods.proposeAdmin(adminKey, salt).accounts({
       caller: userTwo.publicKey
-    }).signers([userTwo]).rpc().catch(e => console.error(e));
+    }).signers([userTwo]).rpc();
+    await program.methods.acceptAdmin(salt).rpc();
     console.log("Transferred admin back to: ", adminKey.toString());
+
+    const state = await program.account.vaultState.fetch(vaultStatePDA);
+    assert.ok(state.admin.equals(adminKey));
+    assert.ok(state.pendingAdmin.equals(anchor.web3.PublicKey.default));
   });
 });
 
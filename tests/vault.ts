@@ -1,8 +1,14 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Vault } from "../target/types/vault";
+import { MockOracle } from "../target/types/mock_oracle";
 import {
   TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  getMintLen,
+  getAssociatedTokenAddressSync,
+  createInitializeTransferFeeConfigInstruction,
   MINT_SIZE,
   createAssociatedTokenAccountInstruction,
   getAssociatedTokenAddress,
@@ -13,6 +19,8 @@ import { assert } from "chai";
 import { BN } from "bn.js";
 import { publicKey } from "@coral-xyz/anchor/dist/cjs/utils";
 
+const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
 describe("vault", () => {
   // Metaplex Constants
   const metadata = {
@@ -23,12 +31,14 @@ describe("vault", () => {
   // Configure client to use local cluster.
   anchor.setProvider(anchor.AnchorProvider.env());
   const program = anchor.workspace.Vault as Program<Vault>;
+  const oracleProgram = anchor.workspace.MockOracle as Program<MockOracle>;
+  const salt: number[] = Array.from({ length: 8 }, () => Math.floor(Math.random() * 256));
   const [vaultStatePDA, vaultStateBump] = anchor.web3.PublicKey.findProgramAddressSync(
-    [Buffer.from("vault-state")],
+    [Buffer.from("vault-state"), Buffer.from(salt)],
     program.programId
   );
   const [vaultMint, vaultTokenBump] = anchor.web3.PublicKey.findProgramAddressSync(
-    [Buffer.from("mint")],
+    [Buffer.from("mint"), vaultStatePDA.toBuffer()],
     program.programId
   );
   const adminWallet = anchor.AnchorProvider.env().wallet;
@@ -38,7 +48,37 @@ describe("vault", () => {
   let userCollat: anchor.web3.PublicKey;
   let userVaultToken: anchor.web3.PublicKey;
   let tokenTwoMintKey: anchor.web3.Keypair;
+  const ROLE_ASSET_MANAGER = 1 << 0;
+  const ROLE_ROLE_MANAGER = 1 << 1;
+  const ROLE_GUARDIAN = 1 << 2;
+
+  const roleAddress = (holder: anchor.web3.PublicKey) => anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("role"), vaultStatePDA.toBuffer(), holder.toBuffer()],
+    program.programId
+  )[0];
+
+  // Queues a timelocked action and returns the accounts needed to execute it
+  const queueOperation = async (action, signer?: anchor.web3.Keypair) => {
+    const state = await program.account.vaultState.fetch(vaultStatePDA);
+    const [operation] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("timelock"), vaultStatePDA.toBuffer(), state.operationCount.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const proposer = signer ? signer.publicKey : adminKey;
+
+    await program.methods.queueOperation(salt, action).accounts({
+      caller: proposer,
+    }).signers(signer ? [signer] : []).rpc();
+
+    return { operation, proposer };
+  };
 
+  // Grants roles through the timelock
+  const grantRole = async (holder: anchor.web3.PublicKey, roles: number) => {
+    await program.methods.grantRole(salt, holder, roles).accounts(
+      await queueOperation({ grantRole: { holder, roles } })
+    ).rpc();
+  };
 
   before(async () => {
     console.log("Vault state: ", vaultStatePDA.toString());
@@ -52,24 +92,29 @@ describe("vault", () => {
     await anchor.AnchorProvider.env().connection.requestAirdrop(depositer.publicKey, 2 * anchor.web3.LAMPORTS_PER_SOL);
 
     // Initialize vault_state and vault_token
-    await program.methods.initializeVaultState(adminKey, metadata).accounts({
+    // A zero timelock delay needs the program built with the short-timelock feature,
+    // which `yarn test` does before running anchor test
+    await program.methods.initializeVaultState(salt, adminKey, metadata, 0).accounts({
       signer: adminKey,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
     }).rpc().catch(e => console.error(e));
     console.log("Admin: ", adminKey.toString());
 
     // Add admin as manager
-    await program.methods.addRoleManager(adminKey).rpc();
-    console.log("Added role manager: ", adminKey.toString());
-
-    await program.methods.addAssetManager(adminKey).rpc();
-    console.log("Added asset manager: ", adminKey.toString());
+    await grantRole(adminKey, ROLE_ROLE_MANAGER | ROLE_ASSET_MANAGER);
+    console.log("Granted role and asset manager: ", adminKey.toString());
 
     // Add admin as whitelisted minter / redeemer
-    await program.methods.whitelistMinter(adminKey).rpc();
+    await program.methods.whitelistMinter(salt, adminKey).rpc();
     console.log("Whitelisted minter: ", adminKey.toString());
 
-    await program.methods.whitelistRedeemer(adminKey).rpc();
+    await program.methods.whitelistRedeemer(salt, adminKey).rpc();
     console.log("Whitelisted redeemer: ", adminKey.toString());
+
+    // Set global mint / redeem caps
+    const maxPerWindow = new anchor.BN("1000000000000000");
+    await program.methods.setLimits(salt, new anchor.BN(9000), maxPerWindow, maxPerWindow).rpc();
+    console.log("Set mint / redeem limits");
   });
   
   it("Initialize Mint and mint tokens", async () => {
@@ -81,7 +126,7 @@ describe("vault", () => {
     const mintAmount = 1000000000000;
 
     userCollat = await getAssociatedTokenAddress(tokenOneMintKey.publicKey, depositer.publicKey);
-    userVaultToken = await getAssociatedTokenAddress(vaultMint, depositer.publicKey);
+    userVaultToken = await getAssociatedTokenAddress(vaultMint, depositer.publicKey, false, TOKEN_2022_PROGRAM_ID);
 
     // Create mint for UserToken and user ATA
     const userTokenMintTx = new anchor.web3.Transaction().add(
@@ -121,7 +166,7 @@ describe("vault", () => {
     console.log("Collat Two Mint key: ", tokenTwoMintKey.publicKey.toString());
     
     const createVaultsTx = new anchor.web3.Transaction().add(
-      createAssociatedTokenAccountInstruction(adminKey, userVaultToken, depositer.publicKey, vaultMint)
+      createAssociatedTokenAccountInstruction(adminKey, userVaultToken, depositer.publicKey, vaultMint, TOKEN_2022_PROGRAM_ID)
     );
 
     await anchor.AnchorProvider.env().sendAndConfirm(createVaultsTx, []);
@@ -147,24 +192,39 @@ describe("vault", () => {
     const redeemRate = new anchor.BN(1000000000);
 
     // Add asset one
-    await program.methods.updateAsset(tokenOneMintKey.publicKey, depositRate, redeemRate).accounts({
+    let queued = await queueOperation({
+      updateAsset: { asset: tokenOneMintKey.publicKey, depositRate, redeemRate },
+    });
+    await program.methods.updateAsset(salt, tokenOneMintKey.publicKey, depositRate, redeemRate).accounts({
       authority: adminKey,
       collateralTokenMint: tokenOneMintKey.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      ...queued,
     }).rpc();
     console.log("Asset added: ", tokenOneMintKey.publicKey.toString());
 
     // Add asset two
-    await program.methods.updateAsset(tokenTwoMintKey.publicKey, depositRate, redeemRate).accounts({
+    queued = await queueOperation({
+      updateAsset: { asset: tokenTwoMintKey.publicKey, depositRate, redeemRate },
+    });
+    await program.methods.updateAsset(salt, tokenTwoMintKey.publicKey, depositRate, redeemRate).accounts({
       authority: adminKey,
       collateralTokenMint: tokenTwoMintKey.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      ...queued,
     }).rpc();
     console.log("Asset added: ", tokenTwoMintKey.publicKey.toString());
 
+    // Set per asset mint / redeem caps
+    const maxPerWindow = new anchor.BN("500000000000000");
+    await program.methods.setAssetLimits(salt, tokenOneMintKey.publicKey, maxPerWindow, maxPerWindow).rpc();
+    await program.methods.setAssetLimits(salt, tokenTwoMintKey.publicKey, maxPerWindow, maxPerWindow).rpc();
+
     // Whitelist depositer as minter and redeemer
-    await program.methods.whitelistMinter(depositer.publicKey).rpc();
+    await program.methods.whitelistMinter(salt, depositer.publicKey).rpc();
     console.log("Whitelisted minter: ", adminKey.toString());
 
-    await program.methods.whitelistRedeemer(depositer.publicKey).rpc();
+    await program.methods.whitelistRedeemer(salt, depositer.publicKey).rpc();
     console.log("Whitelisted redeemer: ", adminKey.toString());
 
     const deposit = new anchor.BN(123456789100);
@@ -178,11 +238,15 @@ describe("vault", () => {
     const collatTokensBefore = callerInfo.value.data.parsed.info.tokenAmount.amount;
     
     // Deposit as user
-    const depositTx = await program.methods.deposit(deposit).accounts({
+    const depositTx = await program.methods.deposit(salt, deposit, new anchor.BN(0), null).accounts({
       callerCollateral: userCollat,
       callerVaultToken: userVaultToken,
       minter: depositer.publicKey,
       collateralTokenMint: tokenOneMintKey.publicKey,
+      oracle: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+      treasuryVaultToken: null,
     }).signers([depositer]).rpc().catch(e => console.error(e));
     
 
@@ -193,11 +257,15 @@ describe("vault", () => {
     const collatTokensAfterDeposit = callerInfo.value.data.parsed.info.tokenAmount.amount;
 
     // Redeem as user
-    const redeemTx = await program.methods.redeem(redeem).accounts({
+    const redeemTx = await program.methods.redeem(salt, redeem, new anchor.BN(0), null).accounts({
       callerCollateral: userCollat,
       callerVaultToken: userVaultToken,
       redeemer: depositer.publicKey,
       collateralTokenMint: tokenOneMintKey.publicKey,
+      oracle: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+      treasuryVaultToken: null,
     }).signers([depositer]).rpc().catch(e => console.error(e));
 
     // Get user balances after redemption
@@ -214,13 +282,810 @@ describe("vault", () => {
     console.log("User collat tokens after redemption: ", collatTokensAfterRedemption);
 
     // Remove admin from whitelisted minters / redeemers
-    await program.methods.removeMinter(adminKey).rpc();
+    await program.methods.removeMinter(salt, adminKey).rpc();
     console.log("Removed minter: ", adminKey.toString());
 
-    await program.methods.removeRedeemer(adminKey).rpc();
+    await program.methods.removeRedeemer(salt, adminKey).rpc();
     console.log("Removed redeemer: ", adminKey.toString());
   });
   
+  it("Rejects deposits over the asset mint limit", async () => {
+    const maxPerWindow = new anchor.BN(1000);
+    await program.methods.setAssetLimits(salt, tokenOneMintKey.publicKey, maxPerWindow, maxPerWindow).rpc();
+
+    try {
+      await program.methods.deposit(salt, new anchor.BN(1000000), new anchor.BN(0), null).accounts({
+        callerCollateral: userCollat,
+        callerVaultToken: userVaultToken,
+        minter: depositer.publicKey,
+        collateralTokenMint: tokenOneMintKey.publicKey,
+        oracle: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        treasuryVaultToken: null,
+      }).signers([depositer]).rpc();
+      assert.fail("Deposit over the mint limit should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "MaxMintExceeded");
+    }
+
+    const resetLimit = new anchor.BN("500000000000000");
+    await program.methods.setAssetLimits(salt, tokenOneMintKey.publicKey, resetLimit, resetLimit).rpc();
+  });
+
+  it("Derives rates from a linked oracle and rejects stale prices", async () => {
+    const feedId = [1, 0, 0, 0, 0, 0, 0, 0];
+    const [priceFeed] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("price-feed"), Buffer.from(feedId)],
+      oracleProgram.programId
+    );
+    const connection = program.provider.connection;
+    const now = await connection.getBlockTime(await connection.getSlot());
+    const setOracle = async (config) => {
+      await program.methods.setOracle(salt, tokenOneMintKey.publicKey, config).accounts(
+        await queueOperation({ setOracle: { asset: tokenOneMintKey.publicKey, config } })
+      ).rpc();
+    };
+
+    // 0.999 vault tokens per asset coin, 6 decimals
+    await oracleProgram.methods.setPrice(feedId, new anchor.BN(999000), new anchor.BN(now)).rpc();
+
+    await setOracle({
+      feed: priceFeed,
+      priceOffset: 8,
+      timestampOffset: 16,
+      priceDecimals: 6,
+      maxAge: 60,
+      maxDeviationBps: 50,
+    });
+
+    let callerInfo = await program.provider.connection.getParsedAccountInfo(userVaultToken);
+    const vaultTokensBefore = new anchor.BN(callerInfo.value.data.parsed.info.tokenAmount.amount);
+
+    await program.methods.deposit(salt, new anchor.BN(1000000), new anchor.BN(0), null).accounts({
+      callerCollateral: userCollat,
+      callerVaultToken: userVaultToken,
+      minter: depositer.publicKey,
+      collateralTokenMint: tokenOneMintKey.publicKey,
+      oracle: priceFeed,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+      treasuryVaultToken: null,
+    }).signers([depositer]).rpc();
+
+    callerInfo = await program.provider.connection.getParsedAccountInfo(userVaultToken);
+    const vaultTokensAfter = new anchor.BN(callerInfo.value.data.parsed.info.tokenAmount.amount);
+    assert.equal(vaultTokensAfter.sub(vaultTokensBefore).toString(), "999000000");
+
+    // Publish a stale price
+    await oracleProgram.methods.setPrice(feedId, new anchor.BN(999000), new anchor.BN(now - 3600)).rpc();
+
+    try {
+      await program.methods.deposit(salt, new anchor.BN(1000000), new anchor.BN(0), null).accounts({
+        callerCollateral: userCollat,
+        callerVaultToken: userVaultToken,
+        minter: depositer.publicKey,
+        collateralTokenMint: tokenOneMintKey.publicKey,
+        oracle: priceFeed,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        treasuryVaultToken: null,
+      }).signers([depositer]).rpc();
+      assert.fail("Deposit with a stale price should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "StalePrice");
+    }
+
+    // A price from the future can't pass the age check
+    await oracleProgram.methods.setPrice(feedId, new anchor.BN(999000), new anchor.BN(now + 3600)).rpc();
+
+    try {
+      await program.methods.deposit(salt, new anchor.BN(1000000), new anchor.BN(0), null).accounts({
+        callerCollateral: userCollat,
+        callerVaultToken: userVaultToken,
+        minter: depositer.publicKey,
+        collateralTokenMint: tokenOneMintKey.publicKey,
+        oracle: priceFeed,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        treasuryVaultToken: null,
+      }).signers([depositer]).rpc();
+      assert.fail("Deposit with a price from the future should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "FuturePrice");
+    }
+
+    // Unlink the oracle
+    await setOracle({
+      feed: anchor.web3.PublicKey.default,
+      priceOffset: 0,
+      timestampOffset: 0,
+      priceDecimals: 0,
+      maxAge: 0,
+      maxDeviationBps: 0,
+    });
+  });
+
+  it("Pauses and unpauses minting", async () => {
+    const guardian = anchor.web3.Keypair.generate();
+    await grantRole(guardian.publicKey, ROLE_GUARDIAN);
+
+    // Pause minting of asset one
+    await program.methods.pauseAsset(salt, tokenOneMintKey.publicKey, 1).accounts({
+      caller: guardian.publicKey,
+      callerRole: roleAddress(guardian.publicKey),
+    }).signers([guardian]).rpc();
+
+    try {
+      await program.methods.deposit(salt, new anchor.BN(1000), new anchor.BN(0), null).accounts({
+        callerCollateral: userCollat,
+        callerVaultToken: userVaultToken,
+        minter: depositer.publicKey,
+        collateralTokenMint: tokenOneMintKey.publicKey,
+        oracle: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        treasuryVaultToken: null,
+      }).signers([depositer]).rpc();
+      assert.fail("Deposit while paused should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "Paused");
+    }
+
+    // Only the admin can unpause
+    try {
+      await program.methods.unpauseAsset(salt, tokenOneMintKey.publicKey, 1).accounts({
+        caller: guardian.publicKey,
+      }).signers([guardian]).rpc();
+      assert.fail("Unpause by the guardian should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "NotAdmin");
+    }
+
+    await program.methods.unpauseAsset(salt, tokenOneMintKey.publicKey, 1).rpc();
+  });
+
+  it("Lists assets in the registry and restricts them by status", async () => {
+    const [registryAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("asset-registry"), vaultStatePDA.toBuffer()],
+      program.programId
+    );
+    let registry = await program.account.assetRegistry.fetch(registryAddress);
+    assert.deepEqual(
+      registry.assets.map(entry => entry.mint.toString()),
+      [tokenOneMintKey.publicKey.toString(), tokenTwoMintKey.publicKey.toString()]
+    );
+    assert.equal(registry.assets[0].decimals, 6);
+
+    await program.methods.setAssetStatus(salt, tokenOneMintKey.publicKey, { depositOnly: {} }).rpc();
+    try {
+      await program.methods.redeem(salt, new anchor.BN(1000), new anchor.BN(0), null).accounts({
+        callerCollateral: userCollat,
+        callerVaultToken: userVaultToken,
+        redeemer: depositer.publicKey,
+        collateralTokenMint: tokenOneMintKey.publicKey,
+        oracle: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        treasuryVaultToken: null,
+      }).signers([depositer]).rpc();
+      assert.fail("Redeem of a deposit only asset should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "RedemptionsDisabled");
+    }
+
+    await program.methods.setAssetStatus(salt, tokenOneMintKey.publicKey, { active: {} }).rpc();
+    registry = await program.account.assetRegistry.fetch(registryAddress);
+    assert.ok(registry.assets[0].status.active);
+  });
+
+  it("Retires an empty redeem-only asset and reclaims its accounts", async () => {
+    const retiredMint = anchor.web3.Keypair.generate();
+    const lamports = await program.provider.connection.getMinimumBalanceForRentExemption(MINT_SIZE);
+    await anchor.AnchorProvider.env().sendAndConfirm(new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: adminKey,
+        newAccountPubkey: retiredMint.publicKey,
+        space: MINT_SIZE,
+        programId: TOKEN_PROGRAM_ID,
+        lamports,
+      }),
+      createInitializeMintInstruction(retiredMint.publicKey, 6, adminKey, null),
+    ), [retiredMint]);
+
+    const rate = new anchor.BN(1000000000);
+    const listAsset = async () => program.methods.updateAsset(salt, retiredMint.publicKey, rate, rate).accounts({
+      authority: adminKey,
+      collateralTokenMint: retiredMint.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      ...await queueOperation({
+        updateAsset: { asset: retiredMint.publicKey, depositRate: rate, redeemRate: rate },
+      }),
+    }).rpc();
+    await listAsset();
+
+    const [exchangeRate] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("exchange-rate"), vaultStatePDA.toBuffer(), retiredMint.publicKey.toBuffer()],
+      program.programId
+    );
+    const [programCollateral] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("token-account"), vaultStatePDA.toBuffer(), retiredMint.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // The asset has to wind down through redeem-only first
+    try {
+      await program.methods.retireAsset(salt, retiredMint.publicKey).accounts({
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+      }).rpc();
+      assert.fail("Retiring an active asset should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "AssetNotRedeemOnly");
+    }
+
+    await program.methods.setAssetStatus(salt, retiredMint.publicKey, { redeemOnly: {} }).rpc();
+    await program.methods.retireAsset(salt, retiredMint.publicKey).accounts({
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+    }).rpc();
+
+    const connection = program.provider.connection;
+    assert.isNull(await connection.getAccountInfo(exchangeRate));
+    assert.isNull(await connection.getAccountInfo(programCollateral));
+
+    const [registryAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("asset-registry"), vaultStatePDA.toBuffer()],
+      program.programId
+    );
+    const registry = await program.account.assetRegistry.fetch(registryAddress);
+    const entry = registry.assets.find(entry => entry.mint.equals(retiredMint.publicKey));
+    assert.ok(entry.status.retired);
+
+    // A retired asset cannot be listed again
+    try {
+      await listAsset();
+      assert.fail("Listing a retired asset should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "AssetRetired");
+    }
+  });
+
+  it("Rejects rate updates that break the circuit breaker", async () => {
+    const rate = new anchor.BN(1000000000);
+    const setRates = async (depositRate: anchor.BN, redeemRate: anchor.BN) => {
+      const queued = await queueOperation({
+        updateAsset: { asset: tokenOneMintKey.publicKey, depositRate, redeemRate },
+      });
+      return program.methods.updateAsset(salt, tokenOneMintKey.publicKey, depositRate, redeemRate).accounts({
+        authority: adminKey,
+        collateralTokenMint: tokenOneMintKey.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        ...queued,
+      }).rpc();
+    };
+    const expectError = async (update: Promise<string>, code: string) => {
+      try {
+        await update;
+        assert.fail(`Rate update should fail with ${code}`);
+      } catch (e) {
+        assert.equal(e.error.errorCode.code, code);
+      }
+    };
+
+    // Depositing and redeeming right away would return more than was deposited
+    await expectError(setRates(new anchor.BN(1010000000), rate), "RateArbitrage");
+
+    // Listing caps each update at 5%
+    await expectError(setRates(new anchor.BN(940000000), rate), "RateChangeTooLarge");
+
+    // 1% per update, at most once an hour
+    await program.methods.tightenRateLimits(salt, tokenOneMintKey.publicKey, 100, 0).rpc();
+    await expectError(setRates(new anchor.BN(980000000), rate), "RateChangeTooLarge");
+    await setRates(new anchor.BN(995000000), rate);
+
+    await program.methods.tightenRateLimits(salt, tokenOneMintKey.publicKey, 100, 3600).rpc();
+    await expectError(setRates(rate, rate), "RateUpdateTooSoon");
+
+    // Lifting the limits has to go through the timelock
+    try {
+      await program.methods.tightenRateLimits(salt, tokenOneMintKey.publicKey, 0, 0).rpc();
+      assert.fail("Disabling the rate limits without the timelock should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "LooseningTimelocked");
+    }
+    await program.methods.setRateLimits(salt, tokenOneMintKey.publicKey, 0, 0).accounts(
+      await queueOperation({ setRateLimits: { asset: tokenOneMintKey.publicKey, maxChangeBps: 0, minInterval: 0 } })
+    ).rpc();
+    await setRates(rate, rate);
+  });
+
+  it("Mints against the amount received for Token-2022 collateral with a transfer fee", async () => {
+    const feeMint = anchor.web3.Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    const lamports = await program.provider.connection.getMinimumBalanceForRentExemption(mintLen);
+    const feeCollat = getAssociatedTokenAddressSync(feeMint.publicKey, depositer.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+    // 1% transfer fee
+    const mintTx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: adminKey,
+        newAccountPubkey: feeMint.publicKey,
+        space: mintLen,
+        programId: TOKEN_2022_PROGRAM_ID,
+        lamports,
+      }),
+      createInitializeTransferFeeConfigInstruction(feeMint.publicKey, adminKey, adminKey, 100, BigInt(1000000000), TOKEN_2022_PROGRAM_ID),
+      createInitializeMintInstruction(feeMint.publicKey, 6, adminKey, null, TOKEN_2022_PROGRAM_ID),
+      createAssociatedTokenAccountInstruction(adminKey, feeCollat, depositer.publicKey, feeMint.publicKey, TOKEN_2022_PROGRAM_ID),
+      createMintToInstruction(feeMint.publicKey, feeCollat, adminKey, 1000000000, [], TOKEN_2022_PROGRAM_ID),
+    );
+    await anchor.AnchorProvider.env().sendAndConfirm(mintTx, [feeMint]);
+
+    const rate = new anchor.BN(1000000000);
+    const queued = await queueOperation({
+      updateAsset: { asset: feeMint.publicKey, depositRate: rate, redeemRate: rate },
+    });
+    await program.methods.updateAsset(salt, feeMint.publicKey, rate, rate).accounts({
+      authority: adminKey,
+      collateralTokenMint: feeMint.publicKey,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      ...queued,
+    }).rpc();
+
+    const maxPerWindow = new anchor.BN("500000000000000");
+    await program.methods.setAssetLimits(salt, feeMint.publicKey, maxPerWindow, maxPerWindow).rpc();
+
+    let callerInfo = await program.provider.connection.getParsedAccountInfo(userVaultToken);
+    const vaultTokensBefore = new anchor.BN(callerInfo.value.data.parsed.info.tokenAmount.amount);
+
+    await program.methods.deposit(salt, new anchor.BN(1000000), new anchor.BN(0), null).accounts({
+      callerCollateral: feeCollat,
+      callerVaultToken: userVaultToken,
+      minter: depositer.publicKey,
+      collateralTokenMint: feeMint.publicKey,
+      oracle: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+      treasuryVaultToken: null,
+    }).signers([depositer]).rpc();
+
+    callerInfo = await program.provider.connection.getParsedAccountInfo(userVaultToken);
+    const vaultTokensAfter = new anchor.BN(callerInfo.value.data.parsed.info.tokenAmount.amount);
+    assert.equal(vaultTokensAfter.sub(vaultTokensBefore).toString(), "990000000");
+  });
+
+  it("Charges mint fees to the treasury", async () => {
+    const treasury = await getAssociatedTokenAddress(vaultMint, adminKey, false, TOKEN_2022_PROGRAM_ID);
+    await anchor.AnchorProvider.env().sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(adminKey, treasury, adminKey, vaultMint, TOKEN_2022_PROGRAM_ID)
+    ), []);
+    await program.methods.setTreasury(salt, treasury).accounts(
+      await queueOperation({ setTreasury: { treasury } })
+    ).rpc();
+
+    // 10 bps mint fee
+    await program.methods.setFees(salt, tokenOneMintKey.publicKey, 10, 0).accounts(
+      await queueOperation({ setFees: { asset: tokenOneMintKey.publicKey, mintFeeBps: 10, redeemFeeBps: 0 } })
+    ).rpc();
+
+    await program.methods.deposit(salt, new anchor.BN(1000000), new anchor.BN(0), null).accounts({
+      callerCollateral: userCollat,
+      callerVaultToken: userVaultToken,
+      minter: depositer.publicKey,
+      collateralTokenMint: tokenOneMintKey.publicKey,
+      oracle: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+      treasuryVaultToken: treasury,
+    }).signers([depositer]).rpc();
+
+    const treasuryInfo = await program.provider.connection.getParsedAccountInfo(treasury);
+    assert.equal(treasuryInfo.value.data.parsed.info.tokenAmount.amount, "1000000");
+
+    await program.methods.setFees(salt, tokenOneMintKey.publicKey, 0, 0).accounts(
+      await queueOperation({ setFees: { asset: tokenOneMintKey.publicKey, mintFeeBps: 0, redeemFeeBps: 0 } })
+    ).rpc();
+  });
+
+  it("Rejects deposits below the minimum amount out or past expiry", async () => {
+    const accounts = {
+      callerCollateral: userCollat,
+      callerVaultToken: userVaultToken,
+      minter: depositer.publicKey,
+      collateralTokenMint: tokenOneMintKey.publicKey,
+      oracle: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+      treasuryVaultToken: null,
+    };
+
+    // 1 collateral unit mints 1000 vault token units at a 1:1 rate
+    try {
+      await program.methods.deposit(salt, new anchor.BN(1), new anchor.BN(1001), null).accounts(accounts).signers([depositer]).rpc();
+      assert.fail("Deposit below the minimum amount out should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "SlippageExceeded");
+    }
+
+    const slot = await program.provider.connection.getSlot();
+    try {
+      await program.methods.deposit(salt, new anchor.BN(1), new anchor.BN(0), new anchor.BN(slot - 1)).accounts(accounts).signers([depositer]).rpc();
+      assert.fail("Deposit past expiry should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "Expired");
+    }
+
+    await program.methods.deposit(salt, new anchor.BN(1), new anchor.BN(1000), new anchor.BN(slot + 100)).accounts(accounts).signers([depositer]).rpc();
+  });
+
+  it("Deposits at a signed quote and rejects replays", async () => {
+    const quoteSigner = anchor.web3.Keypair.generate();
+    await program.methods.setQuoteSigner(salt, quoteSigner.publicKey).accounts(
+      await queueOperation({ setQuoteSigner: { signer: quoteSigner.publicKey } })
+    ).rpc();
+
+    // Borsh layout of `Quote`, signed behind the domain and the vault state key
+    const encodeQuote = (quote) => Buffer.concat([
+      quote.user.toBuffer(),
+      quote.asset.toBuffer(),
+      Buffer.from([quote.side.mint ? 0 : 1]),
+      quote.amountIn.toArrayLike(Buffer, "le", 8),
+      quote.amountOut.toArrayLike(Buffer, "le", 8),
+      quote.expiry.toTwos(64).toArrayLike(Buffer, "le", 8),
+      quote.nonce.toArrayLike(Buffer, "le", 8),
+    ]);
+    const signQuote = (quote, signer: anchor.web3.Keypair) => anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: signer.secretKey,
+      message: Buffer.concat([Buffer.from("solomon-vault-quote"), vaultStatePDA.toBuffer(), encodeQuote(quote)]),
+    });
+
+    const quote = {
+      user: depositer.publicKey,
+      asset: tokenOneMintKey.publicKey,
+      side: { mint: {} },
+      amountIn: new anchor.BN(1000),
+      amountOut: new anchor.BN(2000000),
+      expiry: new anchor.BN(Math.floor(Date.now() / 1000) + 600),
+      nonce: new anchor.BN(1),
+    };
+    const accounts = {
+      callerCollateral: userCollat,
+      callerVaultToken: userVaultToken,
+      minter: depositer.publicKey,
+      collateralTokenMint: tokenOneMintKey.publicKey,
+      oracle: null,
+      instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+      treasuryVaultToken: null,
+    };
+
+    let callerInfo = await program.provider.connection.getParsedAccountInfo(userVaultToken);
+    const before = new anchor.BN(callerInfo.value.data.parsed.info.tokenAmount.amount);
+
+    await program.methods.depositQuoted(salt, quote).accounts(accounts)
+      .preInstructions([signQuote(quote, quoteSigner)])
+      .signers([depositer]).rpc();
+
+    // The quoted amount is minted regardless of the exchange rate
+    callerInfo = await program.provider.connection.getParsedAccountInfo(userVaultToken);
+    const after = new anchor.BN(callerInfo.value.data.parsed.info.tokenAmount.amount);
+    assert.equal(after.sub(before).toString(), "2000000");
+
+    try {
+      await program.methods.depositQuoted(salt, quote).accounts(accounts)
+        .preInstructions([signQuote(quote, quoteSigner)])
+        .signers([depositer]).rpc();
+      assert.fail("Replaying a quote should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "QuoteAlreadyUsed");
+    }
+
+    const forged = { ...quote, nonce: new anchor.BN(2) };
+    try {
+      await program.methods.depositQuoted(salt, forged).accounts(accounts)
+        .preInstructions([signQuote(forged, depositer)])
+        .signers([depositer]).rpc();
+      assert.fail("A quote not signed by the quote signer should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "InvalidQuoteSignature");
+    }
+  });
+
+  it("Queues redemptions and fulfils them in order", async () => {
+    const accounts = {
+      callerVaultToken: userVaultToken,
+      redeemer: depositer.publicKey,
+      collateralTokenMint: tokenOneMintKey.publicKey,
+      oracle: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      treasuryVaultToken: null,
+    };
+    const ticketAddress = (id: number) => anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("redeem-ticket"), vaultStatePDA.toBuffer(), tokenOneMintKey.publicKey.toBuffer(), new anchor.BN(id).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+    // The first ticket is cancelled, the second one fulfilled and claimed
+    await program.methods.requestRedeem(salt, new anchor.BN(1000000), new anchor.BN(0), null).accounts(accounts).signers([depositer]).rpc();
+    await program.methods.requestRedeem(salt, new anchor.BN(2000000), new anchor.BN(0), null).accounts(accounts).signers([depositer]).rpc();
+
+    const ticket = await program.account.redeemTicket.fetch(ticketAddress(1));
+    assert.equal(ticket.collateral.toString(), "2000");
+    assert.ok(ticket.status.pending);
+
+    await program.methods.cancelRedeem(salt).accounts({
+      ticket: ticketAddress(0),
+      ownerVaultToken: userVaultToken,
+      owner: depositer.publicKey,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    }).signers([depositer]).rpc();
+
+    // Fund the tickets from the asset manager
+    const managerCollat = await getAssociatedTokenAddress(tokenOneMintKey.publicKey, adminKey);
+    await anchor.AnchorProvider.env().sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(adminKey, managerCollat, adminKey, tokenOneMintKey.publicKey),
+      createMintToInstruction(tokenOneMintKey.publicKey, managerCollat, adminKey, 2000),
+    ), []);
+
+    const fulfilAccounts = (id: number) => ({
+      ticket: ticketAddress(id),
+      owner: depositer.publicKey,
+      callerCollateral: managerCollat,
+      collateralTokenMint: tokenOneMintKey.publicKey,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+    try {
+      await program.methods.fulfilRedeem(salt).accounts(fulfilAccounts(1)).rpc();
+      assert.fail("Fulfilling out of order should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "TicketOutOfOrder");
+    }
+
+    // Skips the cancelled ticket, then funds the next one
+    await program.methods.fulfilRedeem(salt).accounts(fulfilAccounts(0)).rpc();
+    await program.methods.fulfilRedeem(salt).accounts(fulfilAccounts(1)).rpc();
+
+    let callerInfo = await program.provider.connection.getParsedAccountInfo(userCollat);
+    const before = new anchor.BN(callerInfo.value.data.parsed.info.tokenAmount.amount);
+
+    await program.methods.claimRedeem(salt).accounts({
+      ticket: ticketAddress(1),
+      ownerCollateral: userCollat,
+      collateralTokenMint: tokenOneMintKey.publicKey,
+      owner: depositer.publicKey,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+    }).signers([depositer]).rpc();
+
+    callerInfo = await program.provider.connection.getParsedAccountInfo(userCollat);
+    const after = new anchor.BN(callerInfo.value.data.parsed.info.tokenAmount.amount);
+    assert.equal(after.sub(before).toString(), "2000");
+    assert.isNull(await program.provider.connection.getAccountInfo(ticketAddress(1)));
+  });
+
+  it("Enforces per user mint quotas", async () => {
+    const accounts = {
+      callerCollateral: userCollat,
+      callerVaultToken: userVaultToken,
+      minter: depositer.publicKey,
+      collateralTokenMint: tokenOneMintKey.publicKey,
+      oracle: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+      treasuryVaultToken: null,
+    };
+    const zero = new anchor.BN(0);
+
+    // 1500 vault token units per window, each deposit of 1 collateral unit mints 1000
+    await program.methods.setUserQuotas(salt, depositer.publicKey, new anchor.BN(9000), new anchor.BN(1500), zero, zero, zero).rpc();
+
+    await program.methods.deposit(salt, new anchor.BN(1), zero, null).accounts(accounts).signers([depositer]).rpc();
+    try {
+      await program.methods.deposit(salt, new anchor.BN(1), zero, null).accounts(accounts).signers([depositer]).rpc();
+      assert.fail("Deposit over the user quota should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "UserMintQuotaExceeded");
+    }
+
+    // Lifetime caps apply on top of the window quotas
+    await program.methods.setUserQuotas(salt, depositer.publicKey, zero, zero, zero, new anchor.BN(1), zero).rpc();
+    try {
+      await program.methods.deposit(salt, new anchor.BN(1), zero, null).accounts(accounts).signers([depositer]).rpc();
+      assert.fail("Deposit over the lifetime cap should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "UserMintQuotaExceeded");
+    }
+
+    await program.methods.setUserQuotas(salt, depositer.publicKey, zero, zero, zero, zero, zero).rpc();
+  });
+
+  it("Normalizes collateral of any supported decimals", async () => {
+    const connection = program.provider.connection;
+    const lamports = await connection.getMinimumBalanceForRentExemption(MINT_SIZE);
+    const balance = async (account: anchor.web3.PublicKey) =>
+      new anchor.BN((await connection.getTokenAccountBalance(account)).value.amount);
+    const zero = new anchor.BN(0);
+
+    // Creates a mint with `decimals`, funds the depositer and tries to list it
+    const listAsset = async (decimals: number, depositRate: anchor.BN, redeemRate: anchor.BN) => {
+      const mint = anchor.web3.Keypair.generate();
+      const collat = getAssociatedTokenAddressSync(mint.publicKey, depositer.publicKey);
+      await anchor.AnchorProvider.env().sendAndConfirm(new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.createAccount({
+          fromPubkey: adminKey,
+          newAccountPubkey: mint.publicKey,
+          space: MINT_SIZE,
+          programId: TOKEN_PROGRAM_ID,
+          lamports,
+        }),
+        createInitializeMintInstruction(mint.publicKey, decimals, adminKey, null),
+        createAssociatedTokenAccountInstruction(adminKey, collat, depositer.publicKey, mint.publicKey),
+        createMintToInstruction(mint.publicKey, collat, adminKey, BigInt("10000000000000000000")),
+      ), [mint]);
+
+      await program.methods.updateAsset(salt, mint.publicKey, depositRate, redeemRate).accounts({
+        authority: adminKey,
+        collateralTokenMint: mint.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        ...await queueOperation({
+          updateAsset: { asset: mint.publicKey, depositRate, redeemRate },
+        }),
+      }).rpc();
+
+      const maxPerWindow = new anchor.BN("500000000000000");
+      await program.methods.setAssetLimits(salt, mint.publicKey, maxPerWindow, maxPerWindow).rpc();
+
+      return { mint: mint.publicKey, collat };
+    };
+
+    // 2 vault tokens per asset coin and back
+    const depositRate = new anchor.BN(2000000000);
+    const redeemRate = new anchor.BN(500000000);
+    for (const decimals of [0, 1, 6, 8, 9, 10, 12, 17, 18]) {
+      const { mint, collat } = await listAsset(decimals, depositRate, redeemRate);
+      const accounts = {
+        collateralTokenMint: mint,
+        oracle: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        treasuryVaultToken: null,
+      };
+      const oneCoin = new anchor.BN(10).pow(new anchor.BN(decimals));
+
+      // One asset coin mints two vault tokens whatever the decimals of the asset
+      const vaultTokensBefore = await balance(userVaultToken);
+      await program.methods.deposit(salt, oneCoin, zero, null).accounts({
+        callerCollateral: collat,
+        callerVaultToken: userVaultToken,
+        minter: depositer.publicKey,
+        ...accounts,
+      }).signers([depositer]).rpc();
+      const minted = (await balance(userVaultToken)).sub(vaultTokensBefore);
+      assert.equal(minted.toString(), "2000000000", `minted for ${decimals} decimals`);
+
+      // Redeeming them returns exactly the coin
+      const collatBefore = await balance(collat);
+      await program.methods.redeem(salt, minted, zero, null).accounts({
+        callerCollateral: collat,
+        callerVaultToken: userVaultToken,
+        redeemer: depositer.publicKey,
+        ...accounts,
+      }).signers([depositer]).rpc();
+      assert.equal((await balance(collat)).sub(collatBefore).toString(), oneCoin.toString(), `redeemed for ${decimals} decimals`);
+
+      const exchangeRate = await program.account.exchangeRate.fetch(
+        anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("exchange-rate"), vaultStatePDA.toBuffer(), mint.toBuffer()], program.programId)[0]
+      );
+      assert.equal(exchangeRate.decimals, decimals);
+    }
+
+    // Collateral finer than a vault token unit would round down to nothing
+    const rate = new anchor.BN(1000000000);
+    const { mint, collat } = await listAsset(18, rate, rate);
+    try {
+      await program.methods.deposit(salt, new anchor.BN(999999999), zero, null).accounts({
+        callerCollateral: collat,
+        callerVaultToken: userVaultToken,
+        minter: depositer.publicKey,
+        collateralTokenMint: mint,
+        oracle: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        treasuryVaultToken: null,
+      }).signers([depositer]).rpc();
+      assert.fail("A deposit worth less than a vault token unit should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "AmountTooSmall");
+    }
+
+    try {
+      await listAsset(19, rate, rate);
+      assert.fail("Listing an asset with 19 decimals should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "UnsupportedDecimals");
+    }
+  });
+
+  it("Deposits for and redeems to other owners", async () => {
+    const client = anchor.web3.Keypair.generate().publicKey;
+    const clientVaultToken = getAssociatedTokenAddressSync(vaultMint, client, false, TOKEN_2022_PROGRAM_ID);
+    const clientCollat = getAssociatedTokenAddressSync(tokenOneMintKey.publicKey, client);
+    await anchor.AnchorProvider.env().sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(adminKey, clientVaultToken, client, vaultMint, TOKEN_2022_PROGRAM_ID),
+      createAssociatedTokenAccountInstruction(adminKey, clientCollat, client, tokenOneMintKey.publicKey),
+    ), []);
+    const balance = async (account: anchor.web3.PublicKey) =>
+      (await program.provider.connection.getTokenAccountBalance(account)).value.amount;
+    const zero = new anchor.BN(0);
+    const depositAccounts = {
+      callerCollateral: userCollat,
+      callerVaultToken: clientVaultToken,
+      minter: depositer.publicKey,
+      collateralTokenMint: tokenOneMintKey.publicKey,
+      oracle: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+      treasuryVaultToken: null,
+    };
+
+    // A plain deposit only mints to the caller
+    try {
+      await program.methods.deposit(salt, new anchor.BN(1), zero, null).accounts(depositAccounts).signers([depositer]).rpc();
+      assert.fail("Deposit into someone else's account should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "NotCallerTokenAccount");
+    }
+
+    await program.methods.depositFor(salt, new anchor.BN(1), zero, null).accounts(depositAccounts).signers([depositer]).rpc();
+    assert.equal(await balance(clientVaultToken), "1000");
+
+    const vaultTokensBefore = new anchor.BN(await balance(userVaultToken));
+    await program.methods.redeemTo(salt, new anchor.BN(1000), zero, null).accounts({
+      callerCollateral: clientCollat,
+      callerVaultToken: userVaultToken,
+      redeemer: depositer.publicKey,
+      collateralTokenMint: tokenOneMintKey.publicKey,
+      oracle: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+      treasuryVaultToken: null,
+    }).signers([depositer]).rpc();
+    assert.equal(await balance(clientCollat), "1");
+    assert.equal(vaultTokensBefore.sub(new anchor.BN(await balance(userVaultToken))).toString(), "1000");
+  });
+
+  it("Closes the permissions of offboarded users", async () => {
+    const user = anchor.web3.Keypair.generate().publicKey;
+    const rentReceiver = anchor.web3.Keypair.generate().publicKey;
+    const [permissions] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault-state"), vaultStatePDA.toBuffer(), user.toBuffer()],
+      program.programId
+    );
+    const connection = program.provider.connection;
+
+    await program.methods.whitelistMinter(salt, user).rpc();
+    try {
+      await program.methods.closePermissions(salt, user).accounts({ rentReceiver }).rpc();
+      assert.fail("Closing the permissions of an active minter should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "PermissionsActive");
+    }
+
+    await program.methods.removeMinter(salt, user).rpc();
+    const rent = (await connection.getAccountInfo(permissions)).lamports;
+    await program.methods.closePermissions(salt, user).accounts({ rentReceiver }).rpc();
+    assert.isNull(await connection.getAccountInfo(permissions));
+    assert.equal(await connection.getBalance(rentReceiver), rent);
+
+    // Revoking everything clears both flags and closes in one step
+    await program.methods.whitelistMinter(salt, user).rpc();
+    await program.methods.whitelistRedeemer(salt, user).rpc();
+    await program.methods.revokeAll(salt, user).accounts({ rentReceiver }).rpc();
+    assert.isNull(await connection.getAccountInfo(permissions));
+    assert.equal(await connection.getBalance(rentReceiver), 2 * rent);
+  });
+
   it("Add withdrawer and withdraw deposited collateral", async () => {
     const withdrawer = anchor.web3.Keypair.generate();
 
@@ -236,18 +1101,99 @@ describe("vault", () => {
     const amt = new anchor.BN(40000);
     
     // Add withdraw address
-    await program.methods.addWithdrawAddress(withdrawerCollat).rpc();
+    await program.methods.addWithdrawAddress(salt, withdrawerCollat).accounts(
+      await queueOperation({ addWithdrawAddress: { address: withdrawerCollat } })
+    ).rpc();
 
     console.log("Added withdraw address: ", withdrawerCollat.toString());
 
     let callerInfo = await program.provider.connection.getParsedAccountInfo(withdrawerCollat);
     const withdrawerBefore = callerInfo.value.data.parsed.info.tokenAmount.amount;
     
+    // Propose the withdraw and require a second approval
+    const state = await program.account.vaultState.fetch(vaultStatePDA);
+    const [proposal] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("withdraw-proposal"), vaultStatePDA.toBuffer(), state.withdrawProposalCount.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const deadline = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+    // Only approvals of current asset managers count, their role accounts are passed along
+    const approverRoles = [adminKey, depositer.publicKey].map(holder => ({
+      pubkey: roleAddress(holder),
+      isSigner: false,
+      isWritable: false,
+    }));
+    await program.methods.proposeWithdraw(salt, tokenOneMintKey.publicKey, amt, withdrawerCollat, deadline).rpc();
+
+    // A withdraw always needs a second approver, the threshold can't be lowered below two
+    try {
+      await program.methods.setWithdrawThreshold(salt, 1).accounts(
+        await queueOperation({ setWithdrawThreshold: { threshold: 1 } })
+      ).rpc();
+      assert.fail("A threshold of one should be rejected");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "InvalidWithdrawThreshold");
+    }
+
+    try {
+      await program.methods.withdraw(salt).accounts({
+        destination: withdrawerCollat,
+        collatMint: tokenOneMintKey.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        proposal,
+        proposer: adminKey,
+      }).remainingAccounts(approverRoles).rpc();
+      assert.fail("Withdraw with a single approval should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "NotEnoughApprovals");
+    }
+
+    await grantRole(depositer.publicKey, ROLE_ASSET_MANAGER);
+    await program.methods.approveWithdraw(salt).accounts({
+      proposal,
+      caller: depositer.publicKey,
+    }).signers([depositer]).rpc();
+
+    // The destination's policy must allow the asset
+    const setWithdrawPolicy = async (allowedMints: anchor.web3.PublicKey[]) => {
+      await program.methods.setWithdrawPolicy(salt, withdrawerCollat, allowedMints, amt, amt).accounts(
+        await queueOperation({
+          setWithdrawPolicy: { destination: withdrawerCollat, allowedMints, maxPerWithdraw: amt, dailyLimit: amt },
+        })
+      ).rpc();
+    };
+    await setWithdrawPolicy([tokenTwoMintKey.publicKey]);
+    try {
+      await program.methods.withdraw(salt).accounts({
+        destination: withdrawerCollat,
+        collatMint: tokenOneMintKey.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        proposal,
+        proposer: adminKey,
+      }).remainingAccounts(approverRoles).rpc();
+      assert.fail("Withdraw of an asset outside the policy should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "AssetNotAllowed");
+    }
+
+    // Only narrowing a policy skips the timelock
+    try {
+      await program.methods.tightenWithdrawPolicy(salt, withdrawerCollat, [tokenOneMintKey.publicKey], amt, amt).rpc();
+      assert.fail("Allowing another asset without the timelock should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "LooseningTimelocked");
+    }
+    await setWithdrawPolicy([tokenOneMintKey.publicKey, tokenTwoMintKey.publicKey]);
+    await program.methods.tightenWithdrawPolicy(salt, withdrawerCollat, [tokenOneMintKey.publicKey], amt, amt).rpc();
+
     // Withdraw
-    const withdrawTx = await program.methods.withdraw(amt).accounts({
+    const withdrawTx = await program.methods.withdraw(salt).accounts({
       destination: withdrawerCollat,
       collatMint: tokenOneMintKey.publicKey,
-    }).signers([]).rpc();
+      tokenProgram: TOKEN_PROGRAM_ID,
+      proposal,
+      proposer: adminKey,
+    }).remainingAccounts(approverRoles).rpc();
     
     callerInfo = await program.provider.connection.getParsedAccountInfo(withdrawerCollat);
     const withdrawerAfter = callerInfo.value.data.parsed.info.tokenAmount.amount;
@@ -255,25 +1201,265 @@ describe("vault", () => {
     console.log("Withdrawer collat tokens before withdraw: ", withdrawerBefore);
     console.log("Withdrawer collat tokens after withdraw: ", withdrawerAfter);
 
+    // Collateral sent to custodians is tracked until it is repaid
+    const [exchangeRate] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("exchange-rate"), vaultStatePDA.toBuffer(), tokenOneMintKey.publicKey.toBuffer()],
+      program.programId
+    );
+    let rate = await program.account.exchangeRate.fetch(exchangeRate);
+    assert.equal(rate.heldExternally.toString(), amt.toString());
+
+    const managerCollat = await getAssociatedTokenAddress(tokenOneMintKey.publicKey, adminKey);
+    await anchor.AnchorProvider.env().sendAndConfirm(new anchor.web3.Transaction().add(
+      createMintToInstruction(tokenOneMintKey.publicKey, managerCollat, adminKey, 10000),
+    ), []);
+    await program.methods.repay(salt, new anchor.BN(10000)).accounts({
+      callerCollateral: managerCollat,
+      collateralTokenMint: tokenOneMintKey.publicKey,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+    }).rpc();
+
+    rate = await program.account.exchangeRate.fetch(exchangeRate);
+    assert.equal(rate.heldExternally.toString(), "30000");
+
+    // Backing counts the collateral in the vault and held externally
+    const backingOf = (mints: anchor.web3.PublicKey[]) => program.methods.backing(salt).remainingAccounts(
+      mints.flatMap(mint => [
+        anchor.web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("exchange-rate"), vaultStatePDA.toBuffer(), mint.toBuffer()],
+          program.programId
+        )[0],
+        anchor.web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("token-account"), vaultStatePDA.toBuffer(), mint.toBuffer()],
+          program.programId
+        )[0],
+      ]).map(pubkey => ({ pubkey, isSigner: false, isWritable: false }))
+    ).view();
+
+    // Every listed asset has to be passed
+    try {
+      await backingOf([tokenOneMintKey.publicKey]);
+      assert.fail("Backing over a subset of the assets should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "InvalidBackingAccounts");
+    }
+
+    const [registryAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("asset-registry"), vaultStatePDA.toBuffer()],
+      program.programId
+    );
+    const registry = await program.account.assetRegistry.fetch(registryAddress);
+    const report = await backingOf(registry.assets.filter(entry => !entry.status.retired).map(entry => entry.mint));
+    const supply = await program.provider.connection.getTokenSupply(vaultMint);
+    assert.equal(report.supply.toString(), supply.value.amount);
+    assert.isTrue(report.backing.gtn(0));
+
     // Remove withdraw address
-    await program.methods.removeWithdrawAddress(withdrawerCollat).rpc();
+    await program.methods.removeWithdrawAddress(salt, withdrawerCollat).rpc();
     console.log("Removed withdraw address: ", withdrawerCollat.toString());
 
-    // Remove admin from managers
-    await program.methods.removeRoleManager(adminKey).rpc();
-    console.log("Removed role manager: ", adminKey.toString());
+    // Remove admin from managers and reclaim the role account
+    await program.methods.revokeRole(salt, adminKey, ROLE_ROLE_MANAGER | ROLE_ASSET_MANAGER).rpc();
+    console.log("Revoked role and asset manager: ", adminKey.toString());
+
+    await program.methods.closeRole(salt, adminKey).rpc();
+    assert.isNull(await program.provider.connection.getAccountInfo(roleAddress(adminKey)));
+  });
+
+  it("Updates the vault token metadata", async () => {
+    const rebrand = { name: 'Solomon USD', symbol: 'SUSD', uri: metadata.uri };
+    try {
+      await program.methods.updateMetadata(salt, rebrand).accounts({
+        caller: depositer.publicKey,
+      }).signers([depositer]).rpc();
+      assert.fail("Metadata update by a non admin should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "NotAdmin");
+    }
+
+    await program.methods.updateMetadata(salt, rebrand).rpc();
 
-    await program.methods.removeAssetManager(adminKey).rpc();
-    console.log("Removed asset manager: ", adminKey.toString());
+    const [metadataPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), vaultMint.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+    const metadataAccount = await program.provider.connection.getAccountInfo(metadataPDA);
+    assert.ok(metadataAccount.data.includes(Buffer.from(rebrand.name)));
+    assert.ok(metadataAccount.data.includes(Buffer.from(rebrand.symbol)));
   });
 
   it("Transfer admin back and forth", async () => {
-    await program.methods.transferAdmin(depositer.publicKey).rpc()
-    console.log("Transfered admin to: ", depositer.publicKey.toString());
+    await program.methods.proposeAdmin(salt, depositer.publicKey).accounts(
+      await queueOperation({ proposeAdmin: { newAdmin: depositer.publicKey } })
+    ).rpc();
+    console.log("Proposed admin: ", depositer.publicKey.toString());
 
-    await program.methods.transferAdmin(adminKey).accounts({
+    // Only the pending admin can accept
+    try {
+      await program.methods.acceptAdmin(salt).rpc();
+      assert.fail("Accept from a non pending admin should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "NotPendingAdmin");
+    }
+
+    await program.methods.acceptAdmin(salt).accounts({
       caller: depositer.publicKey,
     }).signers([depositer]).rpc();
+    console.log("Transfered admin to: ", depositer.publicKey.toString());
+
+    await program.methods.proposeAdmin(salt, adminKey).accounts(
+      await queueOperation({ proposeAdmin: { newAdmin: adminKey } }, depositer)
+    ).rpc();
+    await program.methods.acceptAdmin(salt).rpc();
     console.log("Transfered admin back to: ", adminKey.toString());
   });
+
+  it("Freezes, thaws and seizes holder balances", async () => {
+    const holders = [anchor.web3.Keypair.generate().publicKey, anchor.web3.Keypair.generate().publicKey];
+    const [holderVaultToken, recoveryVaultToken] = holders.map((owner) =>
+      getAssociatedTokenAddressSync(vaultMint, owner, false, TOKEN_2022_PROGRAM_ID));
+    await anchor.AnchorProvider.env().sendAndConfirm(new anchor.web3.Transaction().add(
+      ...holders.map((owner, i) => createAssociatedTokenAccountInstruction(
+        adminKey, [holderVaultToken, recoveryVaultToken][i], owner, vaultMint, TOKEN_2022_PROGRAM_ID)),
+    ), []);
+    await program.methods.depositFor(salt, new anchor.BN(3), new anchor.BN(0), null).accounts({
+      callerCollateral: userCollat,
+      callerVaultToken: holderVaultToken,
+      minter: depositer.publicKey,
+      collateralTokenMint: tokenOneMintKey.publicKey,
+      oracle: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      collateralTokenProgram: TOKEN_PROGRAM_ID,
+      treasuryVaultToken: null,
+    }).signers([depositer]).rpc();
+
+    const balance = async (account: anchor.web3.PublicKey) =>
+      (await program.provider.connection.getTokenAccountBalance(account)).value.amount;
+    const holderAccounts = { holderVaultToken, tokenProgram: TOKEN_2022_PROGRAM_ID };
+    const seize = async (amount: number, recovery: anchor.web3.PublicKey | null) => {
+      const action = { seize: { account: holderVaultToken, amount: new anchor.BN(amount), recovery } };
+      await program.methods.seize(salt, holderVaultToken, new anchor.BN(amount), recovery).accounts({
+        ...(await queueOperation(action)),
+        holderVaultToken,
+        recoveryVaultToken: recovery,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      }).rpc();
+    };
+
+    // Only frozen balances can be seized
+    try {
+      await seize(1000, null);
+      assert.fail("Seizing an active account should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "AccountNotFrozen");
+    }
+
+    const guardian = anchor.web3.Keypair.generate();
+    await grantRole(guardian.publicKey, ROLE_GUARDIAN);
+    await program.methods.freezeHolder(salt).accounts({
+      ...holderAccounts,
+      caller: guardian.publicKey,
+      callerRole: roleAddress(guardian.publicKey),
+    }).signers([guardian]).rpc();
+
+    // Guardians can freeze but only the admin thaws
+    try {
+      await program.methods.thawHolder(salt).accounts({
+        ...holderAccounts,
+        caller: guardian.publicKey,
+        callerRole: roleAddress(guardian.publicKey),
+      }).signers([guardian]).rpc();
+      assert.fail("Guardian should not be able to thaw");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "NotAdmin");
+    }
+
+    await seize(1000, null);
+    assert.equal(await balance(holderVaultToken), "2000");
+
+    await seize(1500, recoveryVaultToken);
+    assert.equal(await balance(holderVaultToken), "500");
+    assert.equal(await balance(recoveryVaultToken), "1500");
+
+    // The holder stays frozen after a seizure until the admin thaws it
+    const frozen = await program.provider.connection.getParsedAccountInfo(holderVaultToken);
+    assert.equal((frozen.value.data as anchor.web3.ParsedAccountData).parsed.info.state, "frozen");
+
+    await program.methods.thawHolder(salt).accounts(holderAccounts).rpc();
+    const thawed = await program.provider.connection.getParsedAccountInfo(holderVaultToken);
+    assert.equal((thawed.value.data as anchor.web3.ParsedAccountData).parsed.info.state, "initialized");
+  });
+
+  it("Delays and cancels timelocked operations", async () => {
+    const guardian = anchor.web3.Keypair.generate();
+    await grantRole(guardian.publicKey, ROLE_GUARDIAN);
+
+    await program.methods.setTimelockDelay(salt, 3600).accounts(
+      await queueOperation({ setTimelockDelay: { delay: 3600 } })
+    ).rpc();
+
+    const address = anchor.web3.Keypair.generate().publicKey;
+    const queued = await queueOperation({ addWithdrawAddress: { address } });
+
+    try {
+      await program.methods.addWithdrawAddress(salt, address).accounts(queued).rpc();
+      assert.fail("Executing before the delay should fail");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "TimelockNotReady");
+    }
+
+    await program.methods.cancelOperation(salt).accounts({
+      caller: guardian.publicKey,
+      callerRole: roleAddress(guardian.publicKey),
+      ...queued,
+    }).signers([guardian]).rpc();
+
+    const info = await program.provider.connection.getAccountInfo(queued.operation);
+    assert.isNull(info);
+  });
+
+  it("Runs independent vault instances side by side", async () => {
+    const otherSalt: number[] = Array.from({ length: 8 }, () => Math.floor(Math.random() * 256));
+    const [otherState] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault-state"), Buffer.from(otherSalt)],
+      program.programId
+    );
+    const [otherMint] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint"), otherState.toBuffer()],
+      program.programId
+    );
+
+    await program.methods.initializeVaultState(otherSalt, adminKey, { ...metadata, symbol: 'EURSOL' }, 0).accounts({
+      signer: adminKey,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    }).rpc();
+
+    const state = await program.account.vaultState.fetch(otherState);
+    assert.ok(state.vaultTokenMint.equals(otherMint));
+    assert.notOk(otherMint.equals(vaultMint));
+
+    // Roles are scoped to their instance, the admin holds none in the new one yet
+    try {
+      await program.methods.whitelistMinter(otherSalt, depositer.publicKey).rpc();
+      assert.fail("Roles of one instance should not apply to another");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "AccountNotInitialized");
+    }
+
+    // The all zero salt maps to the unscoped seeds of the legacy vault
+    const [legacyState] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault-state")],
+      program.programId
+    );
+    try {
+      await program.methods.initializeVaultState(Array(8).fill(0), adminKey, metadata, 0).accounts({
+        signer: adminKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        vaultState: legacyState,
+      }).rpc();
+      assert.fail("The legacy salt should be reserved");
+    } catch (e) {
+      assert.equal(e.error.errorCode.code, "ReservedSalt");
+    }
+  });
  });